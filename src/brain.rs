@@ -0,0 +1,438 @@
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use rand::prelude::*;
+
+// Entrées : direction (x, y) + distance de la nourriture, direction (x, y) + distance de la créature,
+// ratio d'énergie, statut bébé
+pub const BRAIN_INPUT_COUNT: usize = 8;
+pub const BRAIN_HIDDEN_COUNT: usize = 6;
+// Sorties : accélération (x, y) + une intention par CreatureState
+pub const BRAIN_OUTPUT_COUNT: usize = 7;
+
+const BRAIN_WEIGHT_COUNT: usize =
+    (BRAIN_INPUT_COUNT + 1) * BRAIN_HIDDEN_COUNT + (BRAIN_HIDDEN_COUNT + 1) * BRAIN_OUTPUT_COUNT;
+
+const INITIAL_WEIGHT_RANGE: f32 = 1.;
+const WEIGHT_MUTATION_RATE: f32 = 0.1;
+const WEIGHT_MUTATION_STRENGTH: f32 = 0.3;
+
+// Petit réseau feed-forward à une couche cachée, les poids font partie du génome
+#[derive(Debug, Clone)]
+pub struct NeuralBrain {
+    weights: Vec<f32>,
+}
+
+impl NeuralBrain {
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self {
+            weights: (0..BRAIN_WEIGHT_COUNT)
+                .map(|_| rng.random_range(-INITIAL_WEIGHT_RANGE..INITIAL_WEIGHT_RANGE))
+                .collect(),
+        }
+    }
+
+    // Chaque poids est pris au hasard chez l'un des deux parents
+    pub fn crossover(a: &NeuralBrain, b: &NeuralBrain, rng: &mut impl Rng) -> Self {
+        Self {
+            weights: a
+                .weights
+                .iter()
+                .zip(b.weights.iter())
+                .map(|(wa, wb)| if rng.random_bool(0.5) { *wa } else { *wb })
+                .collect(),
+        }
+    }
+
+    pub fn mutate(&mut self, rng: &mut impl Rng) {
+        for weight in self.weights.iter_mut() {
+            if rng.random::<f32>() < WEIGHT_MUTATION_RATE {
+                *weight += rng.random_range(-WEIGHT_MUTATION_STRENGTH..WEIGHT_MUTATION_STRENGTH);
+            }
+        }
+    }
+
+    pub fn forward(&self, inputs: &[f32; BRAIN_INPUT_COUNT]) -> [f32; BRAIN_OUTPUT_COUNT] {
+        let (hidden_weights, output_weights) = self.weights.split_at((BRAIN_INPUT_COUNT + 1) * BRAIN_HIDDEN_COUNT);
+
+        let mut hidden = [0.; BRAIN_HIDDEN_COUNT];
+        for (neuron, weights) in hidden.iter_mut().zip(hidden_weights.chunks(BRAIN_INPUT_COUNT + 1)) {
+            *neuron = activate(inputs, weights);
+        }
+
+        let mut outputs = [0.; BRAIN_OUTPUT_COUNT];
+        for (neuron, weights) in outputs.iter_mut().zip(output_weights.chunks(BRAIN_HIDDEN_COUNT + 1)) {
+            *neuron = activate(&hidden, weights);
+        }
+        outputs
+    }
+}
+
+// Le dernier poids de chaque neurone est le biais
+fn activate(inputs: &[f32], weights: &[f32]) -> f32 {
+    let sum: f32 = inputs.iter().zip(weights.iter()).map(|(i, w)| i * w).sum();
+    (sum + weights[inputs.len()]).tanh()
+}
+
+// ----- NEAT : topologie évolutive -----
+
+// Le nœud de biais vaut toujours 1, il suit directement les entrées
+const NEAT_BIAS_NODE: u32 = BRAIN_INPUT_COUNT as u32;
+const NEAT_FIRST_OUTPUT_NODE: u32 = NEAT_BIAS_NODE + 1;
+const NEAT_FIRST_HIDDEN_NODE: u32 = NEAT_FIRST_OUTPUT_NODE + BRAIN_OUTPUT_COUNT as u32;
+
+const ADD_CONNECTION_RATE: f32 = 0.1;
+const ADD_NODE_RATE: f32 = 0.05;
+const ADD_CONNECTION_ATTEMPTS: u32 = 20;
+const DISABLED_GENE_INHERIT_RATE: f32 = 0.75;
+
+pub struct BrainPlugin;
+
+impl Plugin for BrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InnovationRegistry>();
+    }
+}
+
+// Numéros d'innovation partagés par toute la population, pour aligner les génomes au croisement
+#[derive(Resource)]
+pub struct InnovationRegistry {
+    next_innovation: u32,
+    next_node: u32,
+    connections: HashMap<(u32, u32), u32>,
+    split_nodes: HashMap<u32, u32>,
+}
+
+impl Default for InnovationRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            next_innovation: 0,
+            next_node: NEAT_FIRST_HIDDEN_NODE,
+            connections: HashMap::new(),
+            split_nodes: HashMap::new(),
+        };
+        // Les connexions initiales ont les mêmes numéros que dans NeatBrain::random
+        for (from, to) in initial_neat_connections() {
+            registry.connection_innovation(from, to);
+        }
+        registry
+    }
+}
+
+impl InnovationRegistry {
+    fn connection_innovation(&mut self, from: u32, to: u32) -> u32 {
+        *self.connections.entry((from, to)).or_insert_with(|| {
+            self.next_innovation += 1;
+            self.next_innovation - 1
+        })
+    }
+
+    // Couper la même connexion donne toujours le même nœud, dans toute la population
+    fn split_node(&mut self, innovation: u32) -> u32 {
+        *self.split_nodes.entry(innovation).or_insert_with(|| {
+            self.next_node += 1;
+            self.next_node - 1
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ConnectionGene {
+    innovation: u32,
+    from: u32,
+    to: u32,
+    weight: f32,
+    enabled: bool,
+}
+
+// Génome NEAT : nœuds cachés et connexions, triées par numéro d'innovation
+#[derive(Debug, Clone)]
+pub struct NeatBrain {
+    hidden_nodes: Vec<u32>,
+    connections: Vec<ConnectionGene>,
+}
+
+fn initial_neat_connections() -> impl Iterator<Item = (u32, u32)> {
+    (0..=NEAT_BIAS_NODE).flat_map(|from| {
+        (NEAT_FIRST_OUTPUT_NODE..NEAT_FIRST_HIDDEN_NODE).map(move |to| (from, to))
+    })
+}
+
+impl NeatBrain {
+    // Topologie minimale : toutes les entrées reliées à toutes les sorties
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self {
+            hidden_nodes: Vec::new(),
+            connections: initial_neat_connections()
+                .enumerate()
+                .map(|(innovation, (from, to))| ConnectionGene {
+                    innovation: innovation as u32,
+                    from,
+                    to,
+                    weight: rng.random_range(-INITIAL_WEIGHT_RANGE..INITIAL_WEIGHT_RANGE),
+                    enabled: true,
+                })
+                .collect(),
+        }
+    }
+
+    // Les gènes communs sont pris au hasard, les gènes disjoints et en excès viennent du parent le plus apte
+    pub fn crossover(fitter: &NeatBrain, other: &NeatBrain, rng: &mut impl Rng) -> Self {
+        let other_genes: HashMap<u32, &ConnectionGene> =
+            other.connections.iter().map(|gene| (gene.innovation, gene)).collect();
+
+        let connections: Vec<ConnectionGene> = fitter
+            .connections
+            .iter()
+            .map(|gene| match other_genes.get(&gene.innovation) {
+                Some(other_gene) => {
+                    let mut child_gene = if rng.random_bool(0.5) { gene.clone() } else { (*other_gene).clone() };
+                    child_gene.enabled = (gene.enabled && other_gene.enabled)
+                        || rng.random::<f32>() >= DISABLED_GENE_INHERIT_RATE;
+                    child_gene
+                }
+                None => gene.clone(),
+            })
+            .collect();
+
+        let mut hidden_nodes: Vec<u32> = connections
+            .iter()
+            .flat_map(|gene| [gene.from, gene.to])
+            .filter(|node| *node >= NEAT_FIRST_HIDDEN_NODE)
+            .collect();
+        hidden_nodes.sort_unstable();
+        hidden_nodes.dedup();
+
+        Self { hidden_nodes, connections }
+    }
+
+    pub fn mutate(&mut self, innovations: &mut InnovationRegistry, rng: &mut impl Rng) {
+        for gene in self.connections.iter_mut() {
+            if rng.random::<f32>() < WEIGHT_MUTATION_RATE {
+                gene.weight += rng.random_range(-WEIGHT_MUTATION_STRENGTH..WEIGHT_MUTATION_STRENGTH);
+            }
+        }
+        if rng.random::<f32>() < ADD_CONNECTION_RATE {
+            self.add_connection(innovations, rng);
+        }
+        if rng.random::<f32>() < ADD_NODE_RATE {
+            self.add_node(innovations, rng);
+        }
+    }
+
+    fn add_connection(&mut self, innovations: &mut InnovationRegistry, rng: &mut impl Rng) {
+        let sources: Vec<u32> = (0..=NEAT_BIAS_NODE).chain(self.hidden_nodes.iter().copied()).collect();
+        let targets: Vec<u32> = (NEAT_FIRST_OUTPUT_NODE..NEAT_FIRST_HIDDEN_NODE)
+            .chain(self.hidden_nodes.iter().copied())
+            .collect();
+
+        for _ in 0..ADD_CONNECTION_ATTEMPTS {
+            let (Some(&from), Some(&to)) = (sources.choose(rng), targets.choose(rng)) else { return; };
+            // Pas de doublon et pas de cycle : le réseau reste feed-forward
+            if from == to
+                || self.connections.iter().any(|gene| gene.from == from && gene.to == to)
+                || self.reaches(to, from)
+            {
+                continue;
+            }
+
+            self.insert_connection(ConnectionGene {
+                innovation: innovations.connection_innovation(from, to),
+                from,
+                to,
+                weight: rng.random_range(-INITIAL_WEIGHT_RANGE..INITIAL_WEIGHT_RANGE),
+                enabled: true,
+            });
+            return;
+        }
+    }
+
+    fn add_node(&mut self, innovations: &mut InnovationRegistry, rng: &mut impl Rng) {
+        let enabled: Vec<usize> = (0..self.connections.len()).filter(|i| self.connections[*i].enabled).collect();
+        let Some(&index) = enabled.choose(rng) else { return; };
+
+        let split = self.connections[index].clone();
+        let node = innovations.split_node(split.innovation);
+        if self.hidden_nodes.contains(&node) {
+            return;
+        }
+
+        // La connexion coupée est remplacée par from -> node (poids 1) et node -> to (ancien poids)
+        self.connections[index].enabled = false;
+        self.hidden_nodes.push(node);
+        self.insert_connection(ConnectionGene {
+            innovation: innovations.connection_innovation(split.from, node),
+            from: split.from,
+            to: node,
+            weight: 1.,
+            enabled: true,
+        });
+        self.insert_connection(ConnectionGene {
+            innovation: innovations.connection_innovation(node, split.to),
+            from: node,
+            to: split.to,
+            weight: split.weight,
+            enabled: true,
+        });
+    }
+
+    fn insert_connection(&mut self, gene: ConnectionGene) {
+        let index = self.connections.partition_point(|other| other.innovation < gene.innovation);
+        self.connections.insert(index, gene);
+    }
+
+    fn reaches(&self, from: u32, to: u32) -> bool {
+        let mut stack = vec![from];
+        let mut visited = HashSet::new();
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+            if visited.insert(node) {
+                stack.extend(self.connections.iter().filter(|gene| gene.from == node).map(|gene| gene.to));
+            }
+        }
+        false
+    }
+
+    pub fn forward(&self, inputs: &[f32; BRAIN_INPUT_COUNT]) -> [f32; BRAIN_OUTPUT_COUNT] {
+        let mut values: HashMap<u32, f32> = inputs.iter().enumerate().map(|(i, v)| (i as u32, *v)).collect();
+        values.insert(NEAT_BIAS_NODE, 1.);
+
+        // Ordre topologique : un nœud est calculé quand toutes ses entrées le sont
+        let enabled: Vec<&ConnectionGene> = self.connections.iter().filter(|gene| gene.enabled).collect();
+        let mut pending: Vec<u32> = self
+            .hidden_nodes
+            .iter()
+            .copied()
+            .chain(NEAT_FIRST_OUTPUT_NODE..NEAT_FIRST_HIDDEN_NODE)
+            .collect();
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|node| {
+                enabled.iter().filter(|gene| gene.to == *node).all(|gene| values.contains_key(&gene.from))
+            });
+            // Un nœud caché dont une entrée n'est jamais calculable reste à 0
+            let node = pending.remove(ready.unwrap_or(0));
+            let sum: f32 = enabled
+                .iter()
+                .filter(|gene| gene.to == node)
+                .map(|gene| values.get(&gene.from).copied().unwrap_or(0.) * gene.weight)
+                .sum();
+            values.insert(node, sum.tanh());
+        }
+
+        let mut outputs = [0.; BRAIN_OUTPUT_COUNT];
+        for (i, output) in outputs.iter_mut().enumerate() {
+            *output = values[&(NEAT_FIRST_OUTPUT_NODE + i as u32)];
+        }
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HIDDEN_A: u32 = NEAT_FIRST_HIDDEN_NODE;
+    const HIDDEN_B: u32 = NEAT_FIRST_HIDDEN_NODE + 1;
+
+    fn gene(innovation: u32, from: u32, to: u32, weight: f32, enabled: bool) -> ConnectionGene {
+        ConnectionGene { innovation, from, to, weight, enabled }
+    }
+
+    // Toutes les connexions initiales sont désactivées sauf celle de l'innovation donnée
+    fn brain_with_single_connection(innovation: u32, rng: &mut impl Rng) -> NeatBrain {
+        let mut brain = NeatBrain::random(rng);
+        for gene in brain.connections.iter_mut() {
+            gene.enabled = gene.innovation == innovation;
+        }
+        brain
+    }
+
+    fn innovation_numbers(brain: &NeatBrain) -> Vec<u32> {
+        brain.connections.iter().map(|gene| gene.innovation).collect()
+    }
+
+    #[test]
+    fn add_connection_never_creates_a_cycle() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut innovations = InnovationRegistry::default();
+        let mut brain = NeatBrain::random(&mut rng);
+        for _ in 0..10 {
+            brain.add_node(&mut innovations, &mut rng);
+        }
+
+        for _ in 0..200 {
+            brain.add_connection(&mut innovations, &mut rng);
+        }
+
+        for gene in &brain.connections {
+            assert!(!brain.reaches(gene.to, gene.from), "cycle par {} -> {}", gene.from, gene.to);
+        }
+    }
+
+    #[test]
+    fn reaches_follows_connections_in_their_direction() {
+        let brain = NeatBrain {
+            hidden_nodes: vec![HIDDEN_A, HIDDEN_B],
+            connections: vec![gene(0, 0, HIDDEN_A, 1., true), gene(1, HIDDEN_A, HIDDEN_B, 1., true)],
+        };
+
+        assert!(brain.reaches(0, HIDDEN_B));
+        assert!(!brain.reaches(HIDDEN_B, 0));
+    }
+
+    #[test]
+    fn add_node_reuses_ids_across_genomes() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut innovations = InnovationRegistry::default();
+        let mut brain_a = brain_with_single_connection(3, &mut rng);
+        let mut brain_b = brain_with_single_connection(3, &mut rng);
+
+        brain_a.add_node(&mut innovations, &mut rng);
+        brain_b.add_node(&mut innovations, &mut rng);
+
+        assert_eq!(brain_a.hidden_nodes, brain_b.hidden_nodes);
+        assert_eq!(innovation_numbers(&brain_a), innovation_numbers(&brain_b));
+        assert!(!brain_a.connections[3].enabled);
+    }
+
+    #[test]
+    fn crossover_aligns_by_innovation_and_keeps_fitter_disjoint_genes() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut innovations = InnovationRegistry::default();
+        let mut fitter = brain_with_single_connection(0, &mut rng);
+        let mut other = brain_with_single_connection(1, &mut rng);
+        fitter.add_node(&mut innovations, &mut rng);
+        other.add_node(&mut innovations, &mut rng);
+
+        let child = NeatBrain::crossover(&fitter, &other, &mut rng);
+
+        assert_eq!(innovation_numbers(&child), innovation_numbers(&fitter));
+        assert_eq!(child.hidden_nodes, fitter.hidden_nodes);
+        for (child_gene, fitter_gene) in child.connections.iter().zip(&fitter.connections) {
+            let other_gene = other.connections.iter().find(|gene| gene.innovation == child_gene.innovation);
+            assert_eq!((child_gene.from, child_gene.to), (fitter_gene.from, fitter_gene.to));
+            assert!(child_gene.weight == fitter_gene.weight || other_gene.is_some_and(|gene| gene.weight == child_gene.weight));
+        }
+    }
+
+    #[test]
+    fn forward_ignores_disabled_and_orphan_hidden_nodes() {
+        let output = NEAT_FIRST_OUTPUT_NODE;
+        let brain = NeatBrain {
+            hidden_nodes: vec![HIDDEN_A, HIDDEN_B],
+            connections: vec![
+                gene(0, NEAT_BIAS_NODE, output, 0.5, true),
+                // Entrée de HIDDEN_A désactivée, HIDDEN_B n'a aucune entrée
+                gene(1, 0, HIDDEN_A, 1., false),
+                gene(2, HIDDEN_A, output, 1., true),
+                gene(3, HIDDEN_B, output, 1., true),
+            ],
+        };
+
+        let outputs = brain.forward(&[1.; BRAIN_INPUT_COUNT]);
+
+        assert!((outputs[0] - 0.5_f32.tanh()).abs() < 1e-6);
+        assert!(outputs[1..].iter().all(|output| *output == 0.));
+    }
+}
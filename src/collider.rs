@@ -1,73 +1,169 @@
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 
-use crate::rigidbody::Accel;
+use crate::rigidbody::{Mass, PhysicsSet, Velocity};
+
+// Pénétration tolérée avant correction, et part de la pénétration corrigée à chaque frame
+const PENETRATION_SLOP: f32 = 0.5;
+const POSITIONAL_CORRECTION_RATE: f32 = 0.8;
+
+// Couches de collision, combinables avec |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionLayers(pub u32);
+
+impl CollisionLayers {
+    pub const CREATURE: CollisionLayers = CollisionLayers(1 << 0);
+    pub const FOOD: CollisionLayers = CollisionLayers(1 << 1);
+    pub const OBSTACLE: CollisionLayers = CollisionLayers(1 << 2);
+    pub const SENSOR: CollisionLayers = CollisionLayers(1 << 3);
+
+    pub fn intersects(self, other: CollisionLayers) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl std::ops::BitOr for CollisionLayers {
+    type Output = CollisionLayers;
+
+    fn bitor(self, rhs: CollisionLayers) -> CollisionLayers {
+        CollisionLayers(self.0 | rhs.0)
+    }
+}
 
 #[derive(Component)]
 pub struct Collider {
     pub radius: f32,
-    pub repulsion_force: f32,
+    pub restitution: f32,       // 0 : choc mou, 1 : rebond parfait
+    pub layer: CollisionLayers, // Couches auxquelles appartient le collider
+    pub mask: CollisionLayers,  // Couches avec lesquelles il interagit
+    pub sensor: bool,           // Un capteur émet des événements mais ne repousse rien
+}
+
+impl Collider {
+    // Deux colliders interagissent seulement si chacun est dans le masque de l'autre
+    fn interacts_with(&self, other: &Collider) -> bool {
+        self.mask.intersects(other.layer) && other.mask.intersects(self.layer)
+    }
 }
 
 
+// Émis une seule fois quand deux colliders commencent à se toucher
 #[derive(Event)]
-pub struct CollisionEvent {
+pub struct CollisionStarted {
     pub entity_a: Entity,
     pub entity_b: Entity,
 }
 
+// Émis une seule fois quand le contact cesse (ou qu'une des entités a disparu)
+#[derive(Event)]
+pub struct CollisionEnded {
+    pub entity_a: Entity,
+    pub entity_b: Entity,
+}
+
+// Ensemble des contacts en cours, mis à jour à chaque frame par resolve_collisions,
+// avec la vitesse de rapprochement de chaque paire mesurée avant l'impulsion
+#[derive(Resource, Default)]
+pub struct Contacts {
+    pairs: HashMap<(Entity, Entity), f32>,
+}
+
+impl Contacts {
+    fn key(entity_a: Entity, entity_b: Entity) -> (Entity, Entity) {
+        (entity_a.min(entity_b), entity_a.max(entity_b))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity, f32)> + '_ {
+        self.pairs.iter().map(|(&(entity_a, entity_b), &approach_speed)| (entity_a, entity_b, approach_speed))
+    }
+}
+
 
 pub struct ColliderPlugin;
 
 impl Plugin for ColliderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<CollisionEvent>()
-        .add_systems(Update, resolve_collisions);
+        app.add_event::<CollisionStarted>()
+        .add_event::<CollisionEnded>()
+        .init_resource::<Contacts>()
+        .add_systems(Update, resolve_collisions.in_set(PhysicsSet::Collision));
     }
 }
 
+// Une entité sans rigidbody (vitesse et masse) est immobile
+type ColliderData = (Entity, &'static mut Transform, &'static Collider, Option<(&'static mut Velocity, &'static Mass)>);
+
 fn resolve_collisions(
-    mut query: Query<(Entity, &Transform, &Collider, Option<&mut Accel>)>,
-    mut collision_events: EventWriter<CollisionEvent>,
+    mut query: Query<ColliderData>,
+    mut contacts: ResMut<Contacts>,
+    mut started_events: EventWriter<CollisionStarted>,
+    mut ended_events: EventWriter<CollisionEnded>,
 ) {
+    // Les entités immobiles (nourriture, obstacles) ne se touchent jamais entre elles :
+    // on place les entités mobiles en premier et on ne teste que les paires qui en contiennent une
     let mut entities: Vec<_> = query.iter_mut().collect();
+    entities.sort_by_key(|(_, _, _, rigidbody)| rigidbody.is_none());
+    let moving_count = entities.iter().take_while(|(_, _, _, rigidbody)| rigidbody.is_some()).count();
+    let mut current_pairs = HashMap::new();
 
-    for i in 0..entities.len() {
+    for i in 0..moving_count {
         for j in (i + 1)..entities.len() {
+            if !entities[i].2.interacts_with(entities[j].2) {
+                continue;
+            }
+
             let dir = entities[j].1.translation.xy() - entities[i].1.translation.xy();
             let min_dist = entities[i].2.radius + entities[j].2.radius;
             let dist = dir.length();
 
             if dist < min_dist && dist > 0.0 {
-                // Calculer la force de répulsion
-                let a_repulsion_force = dir.normalize() * (min_dist - dist) * entities[j].2.repulsion_force;
-                let b_repulsion_force = -dir.normalize() * (min_dist - dist) * entities[i].2.repulsion_force;
+                let normal = dir / dist;
+                let restitution = f32::min(entities[i].2.restitution, entities[j].2.restitution);
 
                 let (left, right) = entities.split_at_mut(j);
-                match (&mut left[i].3, &mut right[0].3) {
-                    (Some(a), Some(b)) => {
-                        // Si les deux entités peuvent bouger, elles se repoussent
-                        a.0 -= a_repulsion_force;
-                        b.0 += b_repulsion_force;
-                    }
-                    (Some(a), None) => {
-                        // Si SEULEMENT A peut bouger, il prend toute la force
-                        a.0 -= a_repulsion_force;
-                    }
-                    (None, Some(b)) => {
-                        // Si SEULEMENT B peut bouger, il prend toute la force
-                        b.0 += b_repulsion_force;
-                    }
-                    (None, None) => {
-                        // Si aucun ne peut bouger, on ne fait rien
+                let (a, b) = (&mut left[i], &mut right[0]);
+
+                // Vitesse de rapprochement avant que l'impulsion ne la transforme en vitesse d'éloignement
+                let velocity_a = a.3.as_ref().map_or(Vec2::ZERO, |(velocity, _)| velocity.0);
+                let velocity_b = b.3.as_ref().map_or(Vec2::ZERO, |(velocity, _)| velocity.0);
+                let approach_speed = (velocity_a - velocity_b).dot(normal);
+
+                // Une entité sans rigidbody est immobile : masse infinie
+                let inverse_mass_a = a.3.as_ref().map_or(0., |(_, mass)| 1. / mass.0);
+                let inverse_mass_b = b.3.as_ref().map_or(0., |(_, mass)| 1. / mass.0);
+                let inverse_mass_sum = inverse_mass_a + inverse_mass_b;
+                let is_sensor = a.2.sensor || b.2.sensor;
+
+                if inverse_mass_sum > 0. && !is_sensor {
+                    // Impulsion seulement si les deux entités se rapprochent
+                    if approach_speed > 0. {
+                        let impulse = normal * (1. + restitution) * approach_speed / inverse_mass_sum;
+                        if let Some((velocity, _)) = a.3.as_mut() {
+                            velocity.0 -= impulse * inverse_mass_a;
+                        }
+                        if let Some((velocity, _)) = b.3.as_mut() {
+                            velocity.0 += impulse * inverse_mass_b;
+                        }
                     }
+
+                    // Correction de position : on sépare les entités selon leur masse
+                    let correction = normal * (min_dist - dist - PENETRATION_SLOP).max(0.)
+                        * POSITIONAL_CORRECTION_RATE / inverse_mass_sum;
+                    a.1.translation -= (correction * inverse_mass_a).extend(0.);
+                    b.1.translation += (correction * inverse_mass_b).extend(0.);
                 }
 
-                // Émettre un événement de collision
-                collision_events.send(CollisionEvent {
-                    entity_a: entities[i].0,
-                    entity_b: entities[j].0,
-                });
+                current_pairs.insert(Contacts::key(entities[i].0, entities[j].0), approach_speed);
             }
         }
     }
+
+    // Émettre les événements de début et de fin de contact
+    for &(entity_a, entity_b) in current_pairs.keys().filter(|pair| !contacts.pairs.contains_key(*pair)) {
+        started_events.send(CollisionStarted { entity_a, entity_b });
+    }
+    for &(entity_a, entity_b) in contacts.pairs.keys().filter(|pair| !current_pairs.contains_key(*pair)) {
+        ended_events.send(CollisionEnded { entity_a, entity_b });
+    }
+    contacts.pairs = current_pairs;
 }
@@ -0,0 +1,451 @@
+use std::fmt::Debug;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::brain::{InnovationRegistry, NeatBrain, NeuralBrain, BRAIN_INPUT_COUNT, BRAIN_OUTPUT_COUNT};
+use crate::creature::{order_float, Creature, CreatureState, CreatureStateComponent};
+use crate::rigidbody::{Forces, Mass};
+use crate::steering::{arrive, combine, evade, obstacle_avoidance, pursue, separation, wander};
+
+const ARRIVE_SLOWING_RADIUS: f32 = 150.;
+const MIN_DIET_TO_HUNT: f32 = 0.2;
+// Sous ce rapport de force (ajusté par la prise de risque), la créature fuit
+const FLEE_ADVANTAGE: f32 = 0.5;
+// Une créature dont l'aggressivity atteint cette valeur chasse même rassasiée
+const AGGRESSIVENESS_FOR_FULL_HUNT: f32 = 100.;
+// 👪 À partir de ce niveau de soins, un parent défend ses petits ; un petit rejoint son parent au-delà de cette distance
+const MIN_PARENTAL_CARE_TO_DEFEND: f32 = 0.5;
+const GUARDIAN_FOLLOW_DISTANCE: f32 = 100.;
+const SEPARATION_WEIGHT: f32 = 0.5;
+const OBSTACLE_AVOIDANCE_WEIGHT: f32 = 0.8;
+const OBSTACLE_LOOKAHEAD_TIME: f32 = 0.5;
+// Part de chaque réseau dans le mélange aléatoire, le reste suit la machine à états
+const MIXED_NEURAL_RATE: f32 = 0.25;
+const MIXED_NEAT_RATE: f32 = 0.25;
+
+pub struct ControllerPlugin;
+
+impl Plugin for ControllerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ControllerMix>()
+            .add_systems(Update, switch_controller_mix);
+    }
+}
+
+// Contrôleurs donnés à la population initiale
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerMix {
+    #[default]
+    StateMachine, // 📜 Toutes les créatures suivent la chaîne de priorités
+    Neural,       // 🧬 Toutes ont un réseau de neurones aléatoire
+    Neat,         // 🌱 Toutes ont un réseau NEAT minimal
+    Mixed,        // 🎲 Un peu de chaque, pour les mettre en concurrence
+}
+
+impl ControllerMix {
+    pub fn random_controller(self, rng: &mut impl Rng) -> Controller {
+        match self {
+            ControllerMix::StateMachine => Controller::StateMachine,
+            ControllerMix::Neural => Controller::Neural(NeuralBrain::random(rng)),
+            ControllerMix::Neat => Controller::Neat(NeatBrain::random(rng)),
+            ControllerMix::Mixed => {
+                let roll = rng.random::<f32>();
+                if roll < MIXED_NEURAL_RATE {
+                    Controller::Neural(NeuralBrain::random(rng))
+                } else if roll < MIXED_NEURAL_RATE + MIXED_NEAT_RATE {
+                    Controller::Neat(NeatBrain::random(rng))
+                } else {
+                    Controller::StateMachine
+                }
+            }
+        }
+    }
+}
+
+// Changer de mélange redistribue aussi les contrôleurs de la population en cours
+fn switch_controller_mix(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut controller_mix: ResMut<ControllerMix>,
+    mut controllers: Query<&mut Controller>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        *controller_mix = match *controller_mix {
+            ControllerMix::StateMachine => ControllerMix::Neural,
+            ControllerMix::Neural => ControllerMix::Neat,
+            ControllerMix::Neat => ControllerMix::Mixed,
+            ControllerMix::Mixed => ControllerMix::StateMachine,
+        };
+        let mut rng = rand::rng();
+        for mut controller in controllers.iter_mut() {
+            *controller = controller_mix.random_controller(&mut rng);
+        }
+    }
+}
+
+// Une autre créature telle que perçue
+#[derive(Debug, Clone, Copy)]
+pub struct PerceivedCreature {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    // Indices observables, sans lire le génome de l'autre
+    pub radius: f32,
+    pub energy_ratio: f32,
+    pub attack_power_estimate: f32,
+}
+
+// Réaction face à une autre créature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encounter {
+    Attack,
+    Flee,
+    Ignore,
+}
+
+// Ce que la créature perçoit de son environnement, mis à jour à chaque frame par path_find_to_nearest_target
+#[derive(Component, Debug, Clone, Default)]
+pub struct Perception {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32, // Taille actuelle, qui grandit pendant l'enfance
+    pub energy_ratio: f32,
+    pub is_baby: bool,
+    pub is_in_love: bool,
+    pub wander_direction: Vec2,
+
+    pub nearest_food: Option<Vec2>,
+    pub nearest_creature: Option<PerceivedCreature>, // Hors alliés, dans creature_vision_radius
+    pub nearest_partner: Option<Vec2>,               // Créature amoureuse dans love_vision_radius
+    pub guardian: Option<Vec2>,                      // Parent qui s'occupe du petit
+    pub offspring_threat: Option<PerceivedCreature>, // Créature non alliée qui approche l'un de ses petits
+    pub neighbours: Vec<(Vec2, f32)>,                // Position et rayon des créatures dans creature_vision_radius
+}
+
+// Ce que le contrôleur veut faire : l'accélération est ajoutée aux forces de la frame
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub accel: Vec2,
+    pub state: CreatureState,
+}
+
+// Comportement personnalisé : scripts, règles, contrôleurs déterministes pour les tests...
+pub trait CreatureController: Send + Sync + Debug {
+    fn decide(&self, perception: &Perception, creature: &Creature) -> Decision;
+
+    fn clone_box(&self) -> Box<dyn CreatureController>;
+}
+
+impl Clone for Box<dyn CreatureController> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+// Ce qui décide du comportement de la créature
+#[derive(Component, Debug, Clone, Default)]
+pub enum Controller {
+    #[default]
+    StateMachine,                       // 📜 Chaîne de priorités écrite à la main
+    Neural(NeuralBrain),                // 🧬 Réseau de neurones dont les poids sont dans le génome
+    Neat(NeatBrain),                    // 🌱 Réseau NEAT dont la topologie évolue aussi
+    #[cfg_attr(not(test), allow(dead_code))] // Seuls les tests en branchent pour l'instant
+    Custom(Box<dyn CreatureController>), // 🔌 Contrôleur branché de l'extérieur
+}
+
+impl Controller {
+    // Croisement puis mutation à la naissance, le parent avec le plus d'énergie est considéré comme le plus apte
+    pub fn inherit(
+        a: &Controller,
+        b: &Controller,
+        a_is_fitter: bool,
+        innovations: &mut InnovationRegistry,
+        rng: &mut impl Rng,
+    ) -> Controller {
+        let mut controller = match (a, b) {
+            (Controller::Neural(brain_a), Controller::Neural(brain_b)) => {
+                Controller::Neural(NeuralBrain::crossover(brain_a, brain_b, rng))
+            }
+            (Controller::Neat(brain_a), Controller::Neat(brain_b)) => {
+                if a_is_fitter {
+                    Controller::Neat(NeatBrain::crossover(brain_a, brain_b, rng))
+                } else {
+                    Controller::Neat(NeatBrain::crossover(brain_b, brain_a, rng))
+                }
+            }
+            _ => if rng.random_bool(0.5) { a.clone() } else { b.clone() },
+        };
+        match &mut controller {
+            Controller::StateMachine | Controller::Custom(_) => {}
+            Controller::Neural(brain) => brain.mutate(rng),
+            Controller::Neat(brain) => brain.mutate(innovations, rng),
+        }
+        controller
+    }
+
+    pub fn decide(&self, perception: &Perception, creature: &Creature) -> Decision {
+        match self {
+            Controller::StateMachine => state_machine_decision(perception, creature),
+            Controller::Neural(brain) => network_decision(brain.forward(&network_inputs(perception, creature)), creature),
+            Controller::Neat(brain) => network_decision(brain.forward(&network_inputs(perception, creature)), creature),
+            Controller::Custom(controller) => controller.decide(perception, creature),
+        }
+    }
+}
+
+fn state_machine_decision(perception: &Perception, creature: &Creature) -> Decision {
+    let (position, velocity, max_speed) = (perception.position, perception.velocity, creature.upper_speed);
+    let encounter = perception
+        .nearest_creature
+        .map(|other_creature| (other_creature, assess_encounter(perception, creature, &other_creature)));
+
+    let (state, primary) = if perception.is_in_love {
+        // 🧡 Mode amoureux : Rejoindre l'autre créature amoureuse la plus proche, ou errer en la cherchant
+        let accel = match perception.nearest_partner {
+            Some(partner_position) => {
+                arrive(position, velocity, partner_position, ARRIVE_SLOWING_RADIUS, max_speed, creature.love_accel_factor)
+            }
+            None => wander(position, velocity, perception.wander_direction, max_speed, creature.love_accel_factor),
+        };
+        (CreatureState::InLove, accel)
+    } else if let Some(threat) = perception.offspring_threat.filter(|threat| {
+        creature.parental_care >= MIN_PARENTAL_CARE_TO_DEFEND
+            && fight_advantage(perception, creature, threat) * creature.risk_tolerance >= FLEE_ADVANTAGE
+    }) {
+        // 🐣 Défend ses petits tant que le combat n'est pas perdu d'avance
+        (
+            CreatureState::Attacking,
+            pursue(position, velocity, threat.position, threat.velocity, max_speed, creature.attack_accel_factor),
+        )
+    } else if let Some((other_creature, Encounter::Attack)) = encounter {
+        // 🔥 Mode agressif : Fonce là où sera la cible
+        (
+            CreatureState::Attacking,
+            pursue(position, velocity, other_creature.position, other_creature.velocity, max_speed, creature.attack_accel_factor),
+        )
+    } else if let Some((other_creature, Encounter::Flee)) = encounter {
+        // 🏃‍♂️ Fuit loin de là où sera la menace
+        (
+            CreatureState::Fleeing,
+            evade(position, velocity, other_creature.position, other_creature.velocity, max_speed, creature.flee_accel_factor),
+        )
+    } else if let Some(guardian_position) = perception.guardian.filter(|guardian| guardian.distance(position) > GUARDIAN_FOLLOW_DISTANCE) {
+        // 👪 Le petit reste près de son parent
+        (
+            CreatureState::Neutral,
+            arrive(position, velocity, guardian_position, ARRIVE_SLOWING_RADIUS, max_speed, creature.neutral_accel_factor),
+        )
+    } else if let Some(food_position) = perception.nearest_food {
+        // 🍎 Mode nourriture : Va jusqu'à la nourriture la plus proche
+        (
+            CreatureState::SeekingFood,
+            arrive(position, velocity, food_position, ARRIVE_SLOWING_RADIUS, max_speed, creature.eat_accel_factor),
+        )
+    } else {
+        // 🚶‍♂️ Mode neutre : Erre à la recherche de nourriture
+        (
+            CreatureState::Neutral,
+            wander(position, velocity, perception.wander_direction, max_speed, creature.neutral_accel_factor),
+        )
+    };
+
+    steer(perception, creature, state, primary)
+}
+
+// ⚖️ Rapport de force estimé à partir de ce que l'on voit : > 1 si la créature pense gagner
+fn fight_advantage(perception: &Perception, creature: &Creature, other_creature: &PerceivedCreature) -> f32 {
+    let size_ratio = perception.radius / other_creature.radius.max(f32::EPSILON);
+    let energy_ratio = perception.energy_ratio / other_creature.energy_ratio.max(f32::EPSILON);
+    let power_ratio = (creature.attack_power + 1.) / (other_creature.attack_power_estimate + 1.);
+    size_ratio * energy_ratio.sqrt() * power_ratio
+}
+
+// La prise de risque déplace les seuils : attaquer au-dessus de 1, fuir sous FLEE_ADVANTAGE, ignorer entre les deux
+fn assess_encounter(perception: &Perception, creature: &Creature, other_creature: &PerceivedCreature) -> Encounter {
+    let advantage = fight_advantage(perception, creature, other_creature) * creature.risk_tolerance;
+    // Une créature peu agressive ne chasse que si elle a faim
+    let wants_to_hunt = creature.diet >= MIN_DIET_TO_HUNT
+        && creature.aggressiveness / AGGRESSIVENESS_FOR_FULL_HUNT >= perception.energy_ratio;
+
+    if advantage >= 1. && wants_to_hunt {
+        Encounter::Attack
+    } else if advantage < FLEE_ADVANTAGE {
+        Encounter::Flee
+    } else {
+        Encounter::Ignore
+    }
+}
+
+// Ajoute séparation et évitement au comportement principal, sauf quand la créature cherche le contact
+fn steer(perception: &Perception, creature: &Creature, state: CreatureState, primary: Vec2) -> Decision {
+    let max_accel = creature.accel_factor(state);
+
+    let accel = if matches!(state, CreatureState::Attacking | CreatureState::InLove) {
+        primary
+    } else {
+        combine(
+            &[
+                (primary, 1.),
+                (separation(perception.position, &perception.neighbours, perception.radius, max_accel), SEPARATION_WEIGHT),
+                (
+                    obstacle_avoidance(
+                        perception.position,
+                        perception.velocity,
+                        &perception.neighbours,
+                        perception.radius,
+                        OBSTACLE_LOOKAHEAD_TIME,
+                        max_accel,
+                    ),
+                    OBSTACLE_AVOIDANCE_WEIGHT,
+                ),
+            ],
+            max_accel,
+        )
+    };
+
+    Decision { accel, state }
+}
+
+fn network_inputs(perception: &Perception, creature: &Creature) -> [f32; BRAIN_INPUT_COUNT] {
+    let (food_direction, food_distance) = match perception.nearest_food {
+        Some(food_position) => (
+            (food_position - perception.position).normalize_or_zero(),
+            food_position.distance(perception.position) / creature.food_vision_radius,
+        ),
+        None => (Vec2::ZERO, 1.),
+    };
+    let (creature_direction, creature_distance) = match perception.nearest_creature {
+        Some(other_creature) => (
+            (other_creature.position - perception.position).normalize_or_zero(),
+            other_creature.position.distance(perception.position) / creature.creature_vision_radius,
+        ),
+        None => (Vec2::ZERO, 1.),
+    };
+
+    [
+        food_direction.x,
+        food_direction.y,
+        food_distance,
+        creature_direction.x,
+        creature_direction.y,
+        creature_distance,
+        perception.energy_ratio,
+        if perception.is_baby { 1. } else { 0. },
+    ]
+}
+
+// 🧬 L'intention la plus forte donne l'état et le facteur d'accélération
+fn network_decision(outputs: [f32; BRAIN_OUTPUT_COUNT], creature: &Creature) -> Decision {
+    let state = CreatureState::ALL
+        .into_iter()
+        .zip(outputs[2..].iter())
+        .max_by(|(_, lhs), (_, rhs)| order_float(**lhs, **rhs))
+        .map(|(state, _)| state)
+        .unwrap_or_default();
+
+    Decision {
+        accel: Vec2::new(outputs[0], outputs[1]).clamp_length_max(1.) * creature.accel_factor(state),
+        state,
+    }
+}
+
+pub fn run_controllers(
+    mut creature_query: Query<(&Controller, &Perception, &Creature, &Mass, &mut Forces, &mut CreatureStateComponent)>,
+) {
+    for (controller, perception, creature, mass, mut forces, mut creature_state) in creature_query.iter_mut() {
+        let decision = controller.decide(perception, creature);
+
+        // Mettre à jour l'état de la créature
+        creature_state.state = decision.state;
+
+        // La décision est une accélération : Forces ne reçoit que de vraies forces
+        forces.0 += decision.accel * mass.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 🌿 Exemple de contrôleur à règles : ne se bat jamais, fuit toute créature et cherche la nourriture
+    #[derive(Debug, Clone)]
+    struct ForagerController;
+
+    impl CreatureController for ForagerController {
+        fn decide(&self, perception: &Perception, creature: &Creature) -> Decision {
+            if perception.is_in_love && perception.nearest_partner.is_some() {
+                return state_machine_decision(perception, creature);
+            }
+            let (position, velocity, max_speed) = (perception.position, perception.velocity, creature.upper_speed);
+
+            let (state, primary) = if let Some(other_creature) = perception.nearest_creature {
+                (
+                    CreatureState::Fleeing,
+                    evade(position, velocity, other_creature.position, other_creature.velocity, max_speed, creature.flee_accel_factor),
+                )
+            } else if let Some(food_position) = perception.nearest_food {
+                (
+                    CreatureState::SeekingFood,
+                    arrive(position, velocity, food_position, ARRIVE_SLOWING_RADIUS, max_speed, creature.eat_accel_factor),
+                )
+            } else {
+                (
+                    CreatureState::Neutral,
+                    wander(position, velocity, perception.wander_direction, max_speed, creature.neutral_accel_factor),
+                )
+            };
+
+            steer(perception, creature, state, primary)
+        }
+
+        fn clone_box(&self) -> Box<dyn CreatureController> {
+            Box::new(self.clone())
+        }
+    }
+
+    fn forager() -> (Controller, Creature) {
+        let creature = Creature {
+            upper_speed: 100.,
+            eat_accel_factor: 50.,
+            flee_accel_factor: 80.,
+            neutral_accel_factor: 20.,
+            ..default()
+        };
+        (Controller::Custom(Box::new(ForagerController)), creature)
+    }
+
+    #[test]
+    fn forager_flees_any_creature() {
+        let (controller, creature) = forager();
+        let perception = Perception {
+            nearest_food: Some(Vec2::new(-100., 0.)),
+            nearest_creature: Some(PerceivedCreature {
+                position: Vec2::new(50., 0.),
+                velocity: Vec2::ZERO,
+                radius: 10.,
+                energy_ratio: 1.,
+                attack_power_estimate: 1.,
+            }),
+            ..default()
+        };
+
+        let decision = controller.decide(&perception, &creature);
+
+        assert_eq!(decision.state, CreatureState::Fleeing);
+        assert!(decision.accel.x < 0.);
+    }
+
+    #[test]
+    fn forager_seeks_food_when_alone() {
+        let (controller, creature) = forager();
+        let perception = Perception {
+            nearest_food: Some(Vec2::new(0., 200.)),
+            ..default()
+        };
+
+        let decision = controller.decide(&perception, &creature);
+
+        assert_eq!(decision.state, CreatureState::SeekingFood);
+        assert!(decision.accel.y > 0.);
+        assert!(decision.accel.length() <= creature.eat_accel_factor + 1e-3);
+    }
+}
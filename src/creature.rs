@@ -1,21 +1,59 @@
 use std::cmp::Ordering;
+use std::f32::consts::PI;
 
 use bevy::prelude::*;
 use bevy::time;
 use bevy::utils::HashSet;
 use rand::prelude::*;
 
-use crate::creature_state::{BabyCreature, BabyIndicator, CreatureStateIndicator};
-use crate::collider::{Collider, CollisionEvent};
-use crate::rigidbody::Accel;
+use crate::brain::InnovationRegistry;
+use crate::controller::{run_controllers, Controller, ControllerMix, PerceivedCreature, Perception};
+use crate::creature_state::{update_age, Age, BabyCreature, BabyIndicator, CreatureStateIndicator, Guardian, RefractoryPeriod, BIRTH_SIZE_RATE};
+use crate::collider::{Collider, CollisionEnded, CollisionLayers, CollisionStarted, Contacts};
 use crate::MAP_HEIGHT;
 use crate::MAP_WIDTH;
-use crate::food::Food;
-use crate::rigidbody::Velocity;
+use crate::food::{spawn_corpse, Corpse, Food};
+use crate::metabolism::Metabolism;
+use crate::reproduction::{lay_egg, BirthMode, Egg, Embryo, Reproduction};
+use crate::steering::{update_wander, Wander};
+use crate::rigidbody::{LinearDamping, Mass, MaxSpeed, PhysicsSet, Velocity};
 
 const INITIAL_CREATURE_COUNT: u32 = 60;
 const SPAWN_ENERGY_RATE: f32 = 0.7;
-const PASSIVE_ENERGY_LOSS: f32 = 50.;
+const CREATURE_LINEAR_DAMPING: f32 = 0.5;
+
+// Dégâts d'une morsure pour chaque point d'attack_power
+const BITE_DAMAGE: f32 = 60.;
+// L'armure divise les dégâts par deux quand elle vaut ARMOR_HALF_REDUCTION
+const ARMOR_HALF_REDUCTION: f32 = 50.;
+// Avantage de taille : les dégâts suivent le rapport des rayons à cette puissance
+const SIZE_ADVANTAGE_EXPONENT: f32 = 1.;
+const MAX_SIZE_ADVANTAGE: f32 = 4.;
+// Vitesse d'approche qui double les dégâts de la morsure
+const CHARGE_SPEED_FOR_DOUBLE_DAMAGE: f32 = 500.;
+
+// Énergie contenue dans le corps d'une créature, en part de son max_energy
+const BODY_ENERGY_RATE: f32 = 0.5;
+// Part de l'énergie du corps de la victime récupérée par le tueur
+const KILL_ENERGY_RATE: f32 = 0.5;
+// Une créature ne cherche ni ne mange une nourriture qu'elle digère moins bien que ça
+const MIN_DIET_EFFICIENCY: f32 = 0.1;
+// 🐣 Un petit est menacé quand une créature non alliée s'en approche à moins de cette distance
+const OFFSPRING_GUARD_RADIUS: f32 = 200.;
+// Seuls les petits assez proches du parent profitent de sa nourriture
+const FOOD_SHARING_RADIUS: f32 = 300.;
+// 👀 Le génome des autres est invisible : leur attaque est estimée d'après leur taille actuelle
+const ATTACK_POWER_PER_RADIUS: f32 = MAX_ATTACK_POWER / MAX_HITBOX_RADIUS;
+// 🥚 Une coquille solide (peu vulnérable) coûte jusqu'à cette part de l'énergie de l'embryon en plus
+const EGG_SHELL_ENERGY_RATE: f32 = 0.5;
+// Part maximale de son énergie qu'un parent peut donner à une portée, coquilles comprises
+const MAX_GIVEN_ENERGY_RATE: f32 = 0.9;
+// ⚤ Avec deux sexes, multiplie l'énergie donnée aux petits par chaque parent
+const FEMALE_INVESTMENT_FACTOR: f32 = 1.5;
+const MALE_INVESTMENT_FACTOR: f32 = 0.5;
+// 🧬 Chance de mutation de chaque gène, et amplitude maximale en part de l'intervalle du gène
+const GENE_MUTATION_RATE: f64 = 0.1;
+const GENE_MUTATION_STRENGTH: f32 = 0.1;
 
 const MIN_CREATURE_ENERGY: f32 = 6400.;
 const MAX_CREATURE_ENERGY: f32 = 12800.;
@@ -24,15 +62,27 @@ const MIN_ACCEL: f32 = 1.;
 const MAX_ACCEL: f32 = 500.;
 const MIN_UPPER_SPEED: f32 = 1.;
 const MAX_UPPER_SPEED: f32 = 1000.;
+const MIN_WANDER_TURN_RATE: f32 = 0.1;
+const MAX_WANDER_TURN_RATE: f32 = 6.;
+const MIN_WANDER_PERSISTENCE: f32 = 0.5;
+const MAX_WANDER_PERSISTENCE: f32 = 5.;
 
 const MIN_CREATURE_AGGRESSIVENESS: f32 = 0.;
 const MAX_CREATURE_AGGRESSIVENESS: f32 = 100.;
+const MIN_RISK_TOLERANCE: f32 = 0.5;
+const MAX_RISK_TOLERANCE: f32 = 2.;
 const MIN_ATTACK_POWER: f32 = 0.;
 const MAX_ATTACK_POWER: f32 = 100.;
+const MIN_ARMOR: f32 = 0.;
+const MAX_ARMOR: f32 = 100.;
+const MIN_BITE_COOLDOWN: f32 = 0.2;
+const MAX_BITE_COOLDOWN: f32 = 3.;
+const MIN_DIET: f32 = 0.;
+const MAX_DIET: f32 = 1.;
 const MIN_HITBOX_RADIUS: f32 = 5.;
 const MAX_HITBOX_RADIUS: f32 = 100.;
-const MIN_HITBOX_REPULSION: f32 = 10.;
-const MAX_HITBOX_REPULSION: f32 = 1000.;
+const MIN_HITBOX_RESTITUTION: f32 = 0.;
+const MAX_HITBOX_RESTITUTION: f32 = 1.;
 
 const MIN_CREATURE_VISION_RADIUS: f32 = 10.;
 const MAX_CREATURE_VISION_RADIUS: f32 = 500.;
@@ -43,10 +93,26 @@ const MAX_LOVE_VISION_RADIUS: f32 = 10000.;
 
 const MIN_WANT_TO_LOVE_RATE: f32 = 0.1;
 const MAX_WANT_TO_LOVE_RATE: f32 = 0.9;
+const MIN_ASEXUALITY: f32 = 0.;
+const MAX_ASEXUALITY: f32 = 1.;
 const MIN_ENERGY_RATE_GIVEN_TO_BABY: f32 = 0.1;
 const MAX_ENERGY_RATE_GIVEN_TO_BABY: f32 = 0.9;
+const MIN_LITTER_SIZE: f32 = 1.;
+const MAX_LITTER_SIZE: f32 = 5.;
+const MIN_REFRACTORY_PERIOD: f32 = 5.;
+const MAX_REFRACTORY_PERIOD: f32 = 60.;
+const MIN_FEMALE_RATE: f32 = 0.1;
+const MAX_FEMALE_RATE: f32 = 0.9;
+const MIN_PARENTAL_CARE: f32 = 0.;
+const MAX_PARENTAL_CARE: f32 = 1.;
+const MIN_INCUBATION_TIME: f32 = 5.;
+const MAX_INCUBATION_TIME: f32 = 30.;
+const MIN_EGG_VULNERABILITY: f32 = 0.1;
+const MAX_EGG_VULNERABILITY: f32 = 1.;
 const MIN_IS_A_CHILD_TIME: f32 = 5.;
 const MAX_IS_A_CHILD_TIME: f32 = 30.;
+const MIN_LIFESPAN: f32 = 120.;
+const MAX_LIFESPAN: f32 = 600.;
 
 
 pub struct CreaturePlugin;
@@ -54,12 +120,22 @@ pub struct CreaturePlugin;
 //Contient tout ce qui est associé au concept de créature
 impl Plugin for CreaturePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_initial_creatures)
-            .add_systems(Update, path_find_to_nearest_target)
-            .add_systems(Update, eat_food)
-            .add_systems(Update, get_tired)
-            .add_systems(Update, collision_interaction)
-            .add_systems(Update, update_energy_bars);
+        app.init_resource::<DeathStats>()
+            .add_event::<CreatureDied>()
+            .add_systems(Startup, (init_creature_assets, spawn_initial_creatures).chain())
+            .add_systems(
+                Update,
+                (update_wander, path_find_to_nearest_target, run_controllers)
+                    .chain()
+                    .in_set(PhysicsSet::Steering),
+            )
+            .add_systems(Update, eat_food.after(PhysicsSet::Collision))
+            .add_systems(Update, get_tired.after(update_age).before(mate)) // Vieillir avant de mourir, et une créature morte de faim ne se bat plus
+            .add_systems(Update, (mate, fight).chain().after(PhysicsSet::Collision))
+            .add_systems(Update, reset_bites.after(PhysicsSet::Collision).before(fight))
+            .add_systems(Update, reproduce_asexually.after(get_tired).before(mate)) // Une créature qui bourgeonne ne s'accouple pas
+            .add_systems(Update, update_energy_bars)
+            .add_systems(Update, (record_deaths, leave_corpses).after(fight));
     }
 }
 
@@ -69,7 +145,7 @@ pub enum CreatureState {
     SeekingFood,   // 🍎 Cherche de la nourriture
     InLove,        // 🧡 Cherche un partenaire
     Attacking,     // 🔥 Attaque une autre créature
-    Fleeing,       // 🏃‍♂️ Fuit une créature plus agressive
+    Fleeing,       // 🏃‍♂️ Fuit une créature qui semble plus forte
 }
 
 impl Default for CreatureState {
@@ -78,9 +154,63 @@ impl Default for CreatureState {
     }
 }
 
+impl CreatureState {
+    pub const ALL: [CreatureState; 5] = [
+        CreatureState::Neutral,
+        CreatureState::SeekingFood,
+        CreatureState::InLove,
+        CreatureState::Attacking,
+        CreatureState::Fleeing,
+    ];
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathCause {
+    Starvation, // 🍽️ Plus d'énergie
+    Killed,     // 🔥 Tuée au combat
+    OldAge,     // ⏳ Fin de la durée de vie
+}
+
+#[derive(Event)]
+pub struct CreatureDied {
+    pub cause: DeathCause,
+    pub position: Vec3,
+    pub radius: f32,
+    pub corpse_energy: f32,
+}
+
+// Nombre de morts par cause depuis le début de la simulation
+#[derive(Resource, Debug, Default)]
+pub struct DeathStats {
+    pub starvation: u32,
+    pub killed: u32,
+    pub old_age: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sex {
+    #[default]
+    Female,
+    Male,
+}
+
+impl Sex {
+    pub fn random(female_rate: f32, rng: &mut impl Rng) -> Sex {
+        if rng.random_bool(female_rate.clamp(0., 1.) as f64) { Sex::Female } else { Sex::Male }
+    }
+
+    // Part de l'énergie donnée aux petits selon le sexe : la femelle investit plus que le mâle
+    pub fn investment_factor(&self) -> f32 {
+        match self {
+            Sex::Female => FEMALE_INVESTMENT_FACTOR,
+            Sex::Male => MALE_INVESTMENT_FACTOR,
+        }
+    }
+}
 
 #[derive(Component, Debug, Clone, Default)]
-#[require(Transform, Velocity)] //Inclu forcément Transform::Default quand on crée le component Creature. On peut l'override
+#[require(Transform, Velocity, Perception, Wander, Age)] //Inclu forcément Transform::Default quand on crée le component Creature. On peut l'override
 pub struct Creature {
     pub energy: f32,
     pub max_energy: f32,
@@ -91,27 +221,216 @@ pub struct Creature {
     pub flee_accel_factor: f32,
     pub love_accel_factor: f32,
     pub upper_speed: f32,
+    pub wander_turn_rate: f32,
+    pub wander_persistence: f32,
 
     pub aggressiveness: f32,
+    pub risk_tolerance: f32,
     pub attack_power: f32,
+    pub armor: f32,
+    pub bite_cooldown: f32,
+    pub diet: f32, // 0 : herbivore, 1 : carnivore
     pub hitbox_radius: f32,
-    pub hitbox_repulsion: f32,
+    pub hitbox_restitution: f32,
 
     pub creature_vision_radius: f32,
     pub food_vision_radius: f32,
     pub love_vision_radius: f32,
 
     pub want_to_love_rate: f32,
+    pub asexuality: f32, // Au-dessus de 0.5, bourgeonne seule plutôt que de chercher un partenaire (si la stratégie est héritable)
     pub energy_rate_given_to_baby: f32,
+    pub litter_size: f32, // Arrondi au nombre de petits qui se partagent l'énergie donnée
+    pub refractory_period: f32, // Secondes sans pouvoir se reproduire après une portée
+    pub female_rate: f32, // ⚤ Proportion de femelles parmi les petits : le sex-ratio évolue
+    pub sex: Sex, // Ne compte que si la reproduction est à deux sexes
+    pub parental_care: f32, // Part de la nourriture partagée avec les petits, au lieu de l'énergie donnée à la naissance
+    pub incubation_time: f32,
+    pub egg_vulnerability: f32, // Chance qu'un prédateur casse l'œuf à chaque contact
     pub is_a_child_time: f32,
+    pub lifespan: f32,
     pub is_in_love: bool,
 
     pub generation: u32,
 }
 
+impl Creature {
+    // Énergie contenue dans le corps à sa taille actuelle : un petit vaut moins qu'un adulte
+    pub fn body_energy(&self, radius: f32) -> f32 {
+        self.max_energy * BODY_ENERGY_RATE * (radius / self.hitbox_radius).powi(2)
+    }
+
+    // Part de l'énergie de la nourriture réellement récupérée selon le régime
+    pub fn diet_efficiency(&self, is_meat: bool) -> f32 {
+        if is_meat { self.diet } else { 1. - self.diet }
+    }
+
+    // 🎲 Génome tiré au hasard pour la population initiale
+    pub fn random(rng: &mut impl Rng) -> Creature {
+        let max_energy = rng.random_range(MIN_CREATURE_ENERGY..MAX_CREATURE_ENERGY);
+        let female_rate = rng.random_range(MIN_FEMALE_RATE..MAX_FEMALE_RATE);
+
+        Creature {
+            energy: SPAWN_ENERGY_RATE * max_energy,
+            max_energy,
+            neutral_accel_factor: rng.random_range(MIN_ACCEL..MAX_ACCEL),
+            eat_accel_factor: rng.random_range(MIN_ACCEL..MAX_ACCEL),
+            attack_accel_factor: rng.random_range(MIN_ACCEL..MAX_ACCEL),
+            flee_accel_factor: rng.random_range(MIN_ACCEL..MAX_ACCEL),
+            love_accel_factor: rng.random_range(MIN_ACCEL..MAX_ACCEL),
+            upper_speed: rng.random_range(MIN_UPPER_SPEED..MAX_UPPER_SPEED),
+            wander_turn_rate: rng.random_range(MIN_WANDER_TURN_RATE..MAX_WANDER_TURN_RATE),
+            wander_persistence: rng.random_range(MIN_WANDER_PERSISTENCE..MAX_WANDER_PERSISTENCE),
+            aggressiveness: rng.random_range(MIN_CREATURE_AGGRESSIVENESS..MAX_CREATURE_AGGRESSIVENESS),
+            risk_tolerance: rng.random_range(MIN_RISK_TOLERANCE..MAX_RISK_TOLERANCE),
+            attack_power: rng.random_range(MIN_ATTACK_POWER..MAX_ATTACK_POWER),
+            armor: rng.random_range(MIN_ARMOR..MAX_ARMOR),
+            bite_cooldown: rng.random_range(MIN_BITE_COOLDOWN..MAX_BITE_COOLDOWN),
+            diet: rng.random_range(MIN_DIET..MAX_DIET),
+            hitbox_radius: rng.random_range(MIN_HITBOX_RADIUS..MAX_HITBOX_RADIUS),
+            hitbox_restitution: rng.random_range(MIN_HITBOX_RESTITUTION..MAX_HITBOX_RESTITUTION),
+            creature_vision_radius: rng.random_range(MIN_CREATURE_VISION_RADIUS..MAX_CREATURE_VISION_RADIUS),
+            food_vision_radius: rng.random_range(MIN_FOOD_VISION_RADIUS..MAX_FOOD_VISION_RADIUS),
+            love_vision_radius: rng.random_range(MIN_LOVE_VISION_RADIUS..MAX_LOVE_VISION_RADIUS),
+            want_to_love_rate: rng.random_range(MIN_WANT_TO_LOVE_RATE..MAX_WANT_TO_LOVE_RATE),
+            asexuality: rng.random_range(MIN_ASEXUALITY..MAX_ASEXUALITY),
+            energy_rate_given_to_baby: rng.random_range(MIN_ENERGY_RATE_GIVEN_TO_BABY..MAX_ENERGY_RATE_GIVEN_TO_BABY),
+            litter_size: rng.random_range(MIN_LITTER_SIZE..MAX_LITTER_SIZE),
+            refractory_period: rng.random_range(MIN_REFRACTORY_PERIOD..MAX_REFRACTORY_PERIOD),
+            female_rate,
+            sex: Sex::random(female_rate, rng),
+            parental_care: rng.random_range(MIN_PARENTAL_CARE..MAX_PARENTAL_CARE),
+            incubation_time: rng.random_range(MIN_INCUBATION_TIME..MAX_INCUBATION_TIME),
+            egg_vulnerability: rng.random_range(MIN_EGG_VULNERABILITY..MAX_EGG_VULNERABILITY),
+            is_a_child_time: rng.random_range(MIN_IS_A_CHILD_TIME..MAX_IS_A_CHILD_TIME),
+            lifespan: rng.random_range(MIN_LIFESPAN..MAX_LIFESPAN),
+            is_in_love: false,
+            generation: 0,
+        }
+    }
+
+    // 🧬 Génome d'un petit : chaque gène est pris au hasard entre ceux des parents
+    pub fn crossover(creature_a: &Creature, creature_b: &Creature, energy: f32, rng: &mut impl Rng) -> Creature {
+        Creature {
+            energy,
+            max_energy: blend_gene(creature_a.max_energy, creature_b.max_energy, rng),
+            neutral_accel_factor: blend_gene(creature_a.neutral_accel_factor, creature_b.neutral_accel_factor, rng),
+            eat_accel_factor: blend_gene(creature_a.eat_accel_factor, creature_b.eat_accel_factor, rng),
+            attack_accel_factor: blend_gene(creature_a.attack_accel_factor, creature_b.attack_accel_factor, rng),
+            flee_accel_factor: blend_gene(creature_a.flee_accel_factor, creature_b.flee_accel_factor, rng),
+            love_accel_factor: blend_gene(creature_a.love_accel_factor, creature_b.love_accel_factor, rng),
+            upper_speed: blend_gene(creature_a.upper_speed, creature_b.upper_speed, rng),
+            wander_turn_rate: blend_gene(creature_a.wander_turn_rate, creature_b.wander_turn_rate, rng),
+            wander_persistence: blend_gene(creature_a.wander_persistence, creature_b.wander_persistence, rng),
+            aggressiveness: blend_gene(creature_a.aggressiveness, creature_b.aggressiveness, rng),
+            risk_tolerance: blend_gene(creature_a.risk_tolerance, creature_b.risk_tolerance, rng),
+            attack_power: blend_gene(creature_a.attack_power, creature_b.attack_power, rng),
+            armor: blend_gene(creature_a.armor, creature_b.armor, rng),
+            bite_cooldown: blend_gene(creature_a.bite_cooldown, creature_b.bite_cooldown, rng),
+            diet: blend_gene(creature_a.diet, creature_b.diet, rng),
+            hitbox_radius: blend_gene(creature_a.hitbox_radius, creature_b.hitbox_radius, rng),
+            hitbox_restitution: blend_gene(creature_a.hitbox_restitution, creature_b.hitbox_restitution, rng),
+            creature_vision_radius: blend_gene(creature_a.creature_vision_radius, creature_b.creature_vision_radius, rng),
+            food_vision_radius: blend_gene(creature_a.food_vision_radius, creature_b.food_vision_radius, rng),
+            love_vision_radius: blend_gene(creature_a.love_vision_radius, creature_b.love_vision_radius, rng),
+            want_to_love_rate: blend_gene(creature_a.want_to_love_rate, creature_b.want_to_love_rate, rng),
+            asexuality: blend_gene(creature_a.asexuality, creature_b.asexuality, rng),
+            energy_rate_given_to_baby: blend_gene(creature_a.energy_rate_given_to_baby, creature_b.energy_rate_given_to_baby, rng),
+            litter_size: blend_gene(creature_a.litter_size, creature_b.litter_size, rng),
+            refractory_period: blend_gene(creature_a.refractory_period, creature_b.refractory_period, rng),
+            female_rate: blend_gene(creature_a.female_rate, creature_b.female_rate, rng),
+            sex: Sex::random((creature_a.female_rate + creature_b.female_rate) / 2., rng),
+            parental_care: blend_gene(creature_a.parental_care, creature_b.parental_care, rng),
+            incubation_time: blend_gene(creature_a.incubation_time, creature_b.incubation_time, rng),
+            egg_vulnerability: blend_gene(creature_a.egg_vulnerability, creature_b.egg_vulnerability, rng),
+            is_a_child_time: blend_gene(creature_a.is_a_child_time, creature_b.is_a_child_time, rng),
+            lifespan: blend_gene(creature_a.lifespan, creature_b.lifespan, rng),
+            is_in_love: false,
+            generation: u32::max(creature_a.generation, creature_b.generation) + 1,
+        }
+    }
+
+    // 🧬 Chaque gène a une chance de muter d'une fraction de son intervalle
+    pub fn mutate(&mut self, rng: &mut impl Rng) {
+        mutate_gene(&mut self.max_energy, MIN_CREATURE_ENERGY, MAX_CREATURE_ENERGY, rng);
+        mutate_gene(&mut self.neutral_accel_factor, MIN_ACCEL, MAX_ACCEL, rng);
+        mutate_gene(&mut self.eat_accel_factor, MIN_ACCEL, MAX_ACCEL, rng);
+        mutate_gene(&mut self.attack_accel_factor, MIN_ACCEL, MAX_ACCEL, rng);
+        mutate_gene(&mut self.flee_accel_factor, MIN_ACCEL, MAX_ACCEL, rng);
+        mutate_gene(&mut self.love_accel_factor, MIN_ACCEL, MAX_ACCEL, rng);
+        mutate_gene(&mut self.upper_speed, MIN_UPPER_SPEED, MAX_UPPER_SPEED, rng);
+        mutate_gene(&mut self.wander_turn_rate, MIN_WANDER_TURN_RATE, MAX_WANDER_TURN_RATE, rng);
+        mutate_gene(&mut self.wander_persistence, MIN_WANDER_PERSISTENCE, MAX_WANDER_PERSISTENCE, rng);
+        mutate_gene(&mut self.aggressiveness, MIN_CREATURE_AGGRESSIVENESS, MAX_CREATURE_AGGRESSIVENESS, rng);
+        mutate_gene(&mut self.risk_tolerance, MIN_RISK_TOLERANCE, MAX_RISK_TOLERANCE, rng);
+        mutate_gene(&mut self.attack_power, MIN_ATTACK_POWER, MAX_ATTACK_POWER, rng);
+        mutate_gene(&mut self.armor, MIN_ARMOR, MAX_ARMOR, rng);
+        mutate_gene(&mut self.bite_cooldown, MIN_BITE_COOLDOWN, MAX_BITE_COOLDOWN, rng);
+        mutate_gene(&mut self.diet, MIN_DIET, MAX_DIET, rng);
+        mutate_gene(&mut self.hitbox_radius, MIN_HITBOX_RADIUS, MAX_HITBOX_RADIUS, rng);
+        mutate_gene(&mut self.hitbox_restitution, MIN_HITBOX_RESTITUTION, MAX_HITBOX_RESTITUTION, rng);
+        mutate_gene(&mut self.creature_vision_radius, MIN_CREATURE_VISION_RADIUS, MAX_CREATURE_VISION_RADIUS, rng);
+        mutate_gene(&mut self.food_vision_radius, MIN_FOOD_VISION_RADIUS, MAX_FOOD_VISION_RADIUS, rng);
+        mutate_gene(&mut self.love_vision_radius, MIN_LOVE_VISION_RADIUS, MAX_LOVE_VISION_RADIUS, rng);
+        mutate_gene(&mut self.want_to_love_rate, MIN_WANT_TO_LOVE_RATE, MAX_WANT_TO_LOVE_RATE, rng);
+        mutate_gene(&mut self.asexuality, MIN_ASEXUALITY, MAX_ASEXUALITY, rng);
+        mutate_gene(&mut self.energy_rate_given_to_baby, MIN_ENERGY_RATE_GIVEN_TO_BABY, MAX_ENERGY_RATE_GIVEN_TO_BABY, rng);
+        mutate_gene(&mut self.litter_size, MIN_LITTER_SIZE, MAX_LITTER_SIZE, rng);
+        mutate_gene(&mut self.refractory_period, MIN_REFRACTORY_PERIOD, MAX_REFRACTORY_PERIOD, rng);
+        mutate_gene(&mut self.female_rate, MIN_FEMALE_RATE, MAX_FEMALE_RATE, rng);
+        mutate_gene(&mut self.parental_care, MIN_PARENTAL_CARE, MAX_PARENTAL_CARE, rng);
+        mutate_gene(&mut self.incubation_time, MIN_INCUBATION_TIME, MAX_INCUBATION_TIME, rng);
+        mutate_gene(&mut self.egg_vulnerability, MIN_EGG_VULNERABILITY, MAX_EGG_VULNERABILITY, rng);
+        mutate_gene(&mut self.is_a_child_time, MIN_IS_A_CHILD_TIME, MAX_IS_A_CHILD_TIME, rng);
+        mutate_gene(&mut self.lifespan, MIN_LIFESPAN, MAX_LIFESPAN, rng);
+    }
+
+    // 👪 Énergie donnée à la portée : les soins en remplacent une partie, que le parent paie ensuite en partageant sa nourriture
+    // Jamais plus que ce que le parent peut payer, coquilles comprises si la portée est pondue
+    pub fn given_energy(&self, investment_factor: f32, birth_mode: BirthMode) -> f32 {
+        let given_energy = (1. - self.parental_care) * investment_factor * self.energy_rate_given_to_baby * self.energy;
+        let shell_rate = match birth_mode {
+            BirthMode::Live => 0.,
+            BirthMode::Egg => EGG_SHELL_ENERGY_RATE,
+        };
+        given_energy.min(MAX_GIVEN_ENERGY_RATE * self.energy / (1. + shell_rate))
+    }
+
+    pub fn litter_count(&self) -> usize {
+        self.litter_size.round().max(1.) as usize
+    }
+
+    // 🥚 Une coquille solide (peu vulnérable) coûte plus cher aux parents
+    pub fn egg_shell_energy(&self) -> f32 {
+        EGG_SHELL_ENERGY_RATE * (1. - self.egg_vulnerability) * self.energy
+    }
+
+    pub fn accel_factor(&self, state: CreatureState) -> f32 {
+        match state {
+            CreatureState::Neutral => self.neutral_accel_factor,
+            CreatureState::SeekingFood => self.eat_accel_factor,
+            CreatureState::InLove => self.love_accel_factor,
+            CreatureState::Attacking => self.attack_accel_factor,
+            CreatureState::Fleeing => self.flee_accel_factor,
+        }
+    }
+}
+
+// Sprite et police partagés par toutes les créatures
+#[derive(Resource)]
+pub struct CreatureAssets {
+    pub image: Handle<Image>,
+    pub font: Handle<Font>,
+}
+
+#[derive(Component)]
+struct BiteCooldown {
+    timer: Timer,
+}
+
 #[derive(Component)]
-struct CreatureAllies {
-    allies: HashSet<Entity>,
+pub struct CreatureAllies {
+    pub allies: HashSet<Entity>,
 }
 
 #[derive(Component)]
@@ -127,40 +446,15 @@ pub struct EnergyBar;
 #[require(Creature)]
 pub struct GenerationLabel;
 
+// Fait apparaître une créature à partir de son génome
 pub fn spawn_creature(
-    x: i32, 
-    y: i32, 
-    
-    energy: f32,
-    max_energy: f32,
-
-    neutral_accel_factor: f32,
-    eat_accel_factor: f32,
-    attack_accel_factor: f32,
-    flee_accel_factor: f32,
-    love_accel_factor: f32,
-    upper_speed: f32,
-
-    aggressiveness: f32,
-    attack_power: f32,
-    hitbox_radius: f32,
-    hitbox_repulsion: f32,
-
-    creature_vision_radius: f32,
-    food_vision_radius: f32,
-    love_vision_radius: f32,
-
-    want_to_love_rate: f32,
-    energy_rate_given_to_baby: f32,
-    is_a_child_time: f32,
-
-    generation: u32,
+    genome: Creature,
+    position: Vec2,
     parents: Option<(Entity, Entity)>,
-
-    commands: &mut Commands, 
-    image: Handle<Image>,
-    font: Handle<Font>,
-) -> Entity{
+    controller: Controller,
+    commands: &mut Commands,
+    assets: &CreatureAssets,
+) -> Entity {
     let mut allies = HashSet::new();
 
     if let Some((parent_a, parent_b)) = parents {
@@ -168,46 +462,41 @@ pub fn spawn_creature(
         allies.insert(parent_b);
     }
 
+    // 🌱 Naît petite, grandit ensuite avec l'âge
+    let birth_radius = genome.hitbox_radius * BIRTH_SIZE_RATE;
+    let generation = genome.generation;
+
     let creature = commands.spawn((
-        Creature {
-            energy,
-            max_energy,
-            neutral_accel_factor,
-            eat_accel_factor,
-            attack_accel_factor,
-            flee_accel_factor,
-            love_accel_factor,
-            upper_speed,
-            aggressiveness,
-            attack_power,
-            hitbox_radius,
-            hitbox_repulsion,
-            creature_vision_radius,
-            food_vision_radius,
-            love_vision_radius,
-            want_to_love_rate,
-            energy_rate_given_to_baby,
-            is_a_child_time,
-            is_in_love: false,
-            generation,
-            
-        },
         CreatureAllies {
             allies,
         },
         CreatureStateComponent {
             state: CreatureState::Neutral,
         },
+        controller,
         BabyCreature {
-            lifetime: Timer::from_seconds(is_a_child_time, TimerMode::Once),
+            lifetime: Timer::from_seconds(genome.is_a_child_time, TimerMode::Once),
+        },
+        BiteCooldown {
+            timer: Timer::from_seconds(genome.bite_cooldown, TimerMode::Once),
         },
-        Transform::from_xyz(x as f32, y as f32, 0.0),
-        Collider { radius: hitbox_radius, repulsion_force: hitbox_repulsion },
+        Transform::from_translation(position.extend(0.)),
+        Collider {
+            radius: birth_radius,
+            restitution: genome.hitbox_restitution,
+            layer: CollisionLayers::CREATURE,
+            mask: CollisionLayers::CREATURE | CollisionLayers::FOOD | CollisionLayers::OBSTACLE | CollisionLayers::SENSOR,
+            sensor: false,
+        },
+        Mass::from_radius(birth_radius),
+        LinearDamping(CREATURE_LINEAR_DAMPING),
+        MaxSpeed(genome.upper_speed),
         Sprite {
-            image,
-            custom_size: Some(Vec2::new(hitbox_radius * 2.0, hitbox_radius * 2.0)),
+            image: assets.image.clone(),
+            custom_size: Some(Vec2::new(birth_radius * 2.0, birth_radius * 2.0)),
             ..default()
         },
+        genome, // En dernier : les composants précédents lisent encore ses gènes
     )).id();
 
     commands.entity(creature).with_children(|parent| {
@@ -256,7 +545,7 @@ pub fn spawn_creature(
             Text2d::new(format!("Gen {}", generation)), 
             Transform::from_xyz(0., -20., 1.0), 
             TextColor(Color::BLACK), 
-            TextFont {font, font_size: 20., ..default()}, 
+            TextFont {font: assets.font.clone(), font_size: 20., ..default()}, 
             TextLayout::default()
         ));
 
@@ -268,261 +557,471 @@ pub fn spawn_creature(
     
 }
 
-fn spawn_initial_creatures(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let mut rng: ThreadRng = rand::rng();
+fn init_creature_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CreatureAssets {
+        image: asset_server.load("sprites/creature.png"),
+        font: asset_server.load("fonts/COOPBL.TTF"),
+    });
+}
 
-    let image = asset_server.load("sprites/creature.png");
-    let font = asset_server.load("fonts/COOPBL.TTF");
-    
+fn spawn_initial_creatures(mut commands: Commands, assets: Res<CreatureAssets>, controller_mix: Res<ControllerMix>) {
+    let mut rng: ThreadRng = rand::rng();
 
     for _ in 0..INITIAL_CREATURE_COUNT {
         let x = rng.random_range(-MAP_WIDTH / 2..MAP_WIDTH / 2);
         let y = rng.random_range(-MAP_HEIGHT / 2..MAP_HEIGHT / 2);
+        let genome = Creature::random(&mut rng);
+        let controller = controller_mix.random_controller(&mut rng);
 
-        let max_energy = rng.random_range(MIN_CREATURE_ENERGY..MAX_CREATURE_ENERGY);
-        let energy = SPAWN_ENERGY_RATE * max_energy;
-        
-        let neutral_accel_factor = rng.random_range(MIN_ACCEL..MAX_ACCEL);
-        let eat_accel_factor = rng.random_range(MIN_ACCEL..MAX_ACCEL);
-        let attack_accel_factor = rng.random_range(MIN_ACCEL..MAX_ACCEL);
-        let flee_accel_factor = rng.random_range(MIN_ACCEL..MAX_ACCEL);
-        let love_accel_factor = rng.random_range(MIN_ACCEL..MAX_ACCEL);
-        let upper_speed = rng.random_range(MIN_UPPER_SPEED..MAX_UPPER_SPEED);
-
-        let aggressiveness = rng.random_range(MIN_CREATURE_AGGRESSIVENESS..MAX_CREATURE_AGGRESSIVENESS);
-        let attack_power = rng.random_range(MIN_ATTACK_POWER..MAX_ATTACK_POWER);
-        let hitbox_radius = rng.random_range(MIN_HITBOX_RADIUS..MAX_HITBOX_RADIUS);
-        let hitbox_repulsion = rng.random_range(MIN_HITBOX_REPULSION..MAX_HITBOX_REPULSION);
-
-        let creature_vision_radius = rng.random_range(MIN_CREATURE_VISION_RADIUS..MAX_CREATURE_VISION_RADIUS);
-        let food_vision_radius = rng.random_range(MIN_FOOD_VISION_RADIUS..MAX_FOOD_VISION_RADIUS);
-        let love_vision_radius = rng.random_range(MIN_LOVE_VISION_RADIUS..MAX_LOVE_VISION_RADIUS);
-
-        let want_to_love_rate = rng.random_range(MIN_WANT_TO_LOVE_RATE..MAX_WANT_TO_LOVE_RATE);
-        let energy_rate_given_to_baby = rng.random_range(MIN_ENERGY_RATE_GIVEN_TO_BABY..MAX_ENERGY_RATE_GIVEN_TO_BABY);
-        let is_a_child_time = rng.random_range(MIN_IS_A_CHILD_TIME..MAX_IS_A_CHILD_TIME);
-
-        spawn_creature(
-            x, y, 
-            energy, max_energy, 
-            neutral_accel_factor, eat_accel_factor, attack_accel_factor, flee_accel_factor, love_accel_factor, upper_speed, 
-            aggressiveness, attack_power, hitbox_radius, hitbox_repulsion, 
-            creature_vision_radius, food_vision_radius, love_vision_radius,
-            want_to_love_rate, energy_rate_given_to_baby, is_a_child_time, 
-            0, None,
-            &mut commands, image.clone(), font.clone(),
-        );
-
+        spawn_creature(genome, Vec2::new(x as f32, y as f32), None, controller, &mut commands, &assets);
     }
 }
 
+type PerceiverData = (
+    &'static mut Perception,
+    &'static Transform,
+    &'static Velocity,
+    &'static Collider,
+    &'static Wander,
+    &'static CreatureAllies,
+    &'static Creature,
+    Has<BabyCreature>,
+    Option<&'static Guardian>,
+    Entity,
+);
+type PerceivedFoodData = (&'static Transform, Has<Corpse>, Option<&'static Egg>);
+
+// Sélection des cibles : remplit la perception que les contrôleurs utilisent pour décider
 fn path_find_to_nearest_target(
-    mut creature_query: Query<(&Transform, &mut Velocity, &mut Accel, &mut CreatureStateComponent, &CreatureAllies, &Creature, )>,
-    food_query: Query<&Transform, With<Food>>,
-    other_creature_query: Query<(&Transform, &Creature, Entity), Without<Food>>, // Exclure les nourritures
+    mut creature_query: Query<PerceiverData>,
+    food_query: Query<PerceivedFoodData, With<Food>>,
+    other_creature_query: Query<(&Transform, &Velocity, &Creature, &Collider, Entity), Without<Food>>, // Exclure les nourritures
+    ward_query: Query<(&Guardian, &Transform)>,
+    reproduction: Res<Reproduction>,
 ) {
-    for (transform, mut velocity, mut accel, mut creature_state, creature_allies, creature) in creature_query.iter_mut() {
-
-        let (target, accel_factor, state) = if creature.is_in_love {
-            // 🧡 Mode amoureux : Chercher une autre créature amoureuse la plus proche
-            (
-                other_creature_query
-                    .iter()
-                    .filter(|(other_transform, other, _)| {
-                        other.is_in_love && other_transform.translation != transform.translation
-                        && other_transform.translation.distance(transform.translation) <= creature.love_vision_radius
-                    })
-                    .min_by(|(lhs_t, _, _), (rhs_t, _, _)| {
-                        order_float(
-                            lhs_t.translation.distance(transform.translation),
-                            rhs_t.translation.distance(transform.translation),
-                        )
-                    })
-                    .map(|(t, _, _)| t.translation),
-                creature.love_accel_factor,
-                CreatureState::InLove,
-            )
-        } else if let Some((other_transform, other_creature, _)) = other_creature_query
+    for (mut perception, transform, velocity, collider, wander, creature_allies, creature, is_baby, guardian, creature_entity) in creature_query.iter_mut() {
+        perception.position = transform.translation.xy();
+        perception.radius = collider.radius;
+        perception.velocity = velocity.0;
+        perception.energy_ratio = creature.energy / creature.max_energy;
+        perception.is_baby = is_baby;
+        perception.is_in_love = creature.is_in_love;
+        perception.wander_direction = wander.direction;
+
+        // 🧡 Autre créature amoureuse la plus proche
+        perception.nearest_partner = other_creature_query
+            .iter()
+            .filter(|(other_transform, _, other, _, _)| {
+                other.is_in_love && reproduction.are_compatible(creature, other)
+                && other_transform.translation != transform.translation
+                && other_transform.translation.distance(transform.translation) <= creature.love_vision_radius
+            })
+            .min_by(|(lhs_t, _, _, _, _), (rhs_t, _, _, _, _)| {
+                order_float(
+                    lhs_t.translation.distance(transform.translation),
+                    rhs_t.translation.distance(transform.translation),
+                )
+            })
+            .map(|(t, _, _, _, _)| t.translation.xy());
+
+        // 🔥 Créature non alliée la plus proche
+        perception.nearest_creature = other_creature_query
             .iter()
-            .filter(|(other_t, _, other_e)| {
+            .filter(|(other_t, _, _, _, other_e)| {
                 other_t.translation.distance(transform.translation) <= creature.creature_vision_radius
                 && other_t.translation != transform.translation
                 && !creature_allies.allies.contains(other_e) // 💥 Ne pas attaquer un allié !
             })
-            .min_by(|(lhs_t, _, _), (rhs_t, _, _)| {
+            .min_by(|(lhs_t, _, _, _, _), (rhs_t, _, _, _, _)| {
                 order_float(
                     lhs_t.translation.distance(transform.translation),
                     rhs_t.translation.distance(transform.translation),
                 )
             })
-        {
-
-            // 🔥 Mode agressif : Attaque ou fuite selon l'aggressivity
-            if creature.aggressiveness > other_creature.aggressiveness {
-                (
-                    Some(other_transform.translation),
-                    creature.attack_accel_factor,
-                    CreatureState::Attacking,
-                ) // Fonce sur la cible
-            } else {
-                // 🏃‍♂️ Fuit dans la direction opposée
-                (
-                    Some(other_transform.translation),
-                    -creature.flee_accel_factor,
-                    CreatureState::Fleeing,
-                )
-            }
-        } else if let Some(food_position) = food_query
+            .map(|(other_t, other_velocity, other_creature, other_collider, _)| PerceivedCreature {
+                position: other_t.translation.xy(),
+                velocity: other_velocity.0,
+                radius: other_collider.radius, // Taille actuelle, pas la taille adulte
+                energy_ratio: other_creature.energy / other_creature.max_energy,
+                attack_power_estimate: other_collider.radius * ATTACK_POWER_PER_RADIUS,
+            });
+
+        // 👪 Parent à suivre pendant l'enfance
+        perception.guardian = guardian
+            .and_then(|guardian| other_creature_query.get(guardian.0).ok())
+            .map(|(guardian_t, _, _, _, _)| guardian_t.translation.xy());
+
+        // 🐣 Créature non alliée la plus proche de l'un de ses petits
+        let wards: Vec<Vec3> = ward_query
+            .iter()
+            .filter(|(ward_guardian, _)| ward_guardian.0 == creature_entity)
+            .map(|(_, ward_t)| ward_t.translation)
+            .collect();
+        perception.offspring_threat = other_creature_query
             .iter()
-            .filter(|t| t.translation.distance(transform.translation) <= creature.food_vision_radius)
-            .min_by(|lhs, rhs| {
+            .filter(|(other_t, _, _, _, other_e)| {
+                other_t.translation.distance(transform.translation) <= creature.creature_vision_radius
+                && !creature_allies.allies.contains(other_e)
+                && wards.iter().any(|ward| ward.distance(other_t.translation) <= OFFSPRING_GUARD_RADIUS)
+            })
+            .min_by(|(lhs_t, _, _, _, _), (rhs_t, _, _, _, _)| {
                 order_float(
-                    lhs.translation.distance(transform.translation),
-                    rhs.translation.distance(transform.translation),
+                    lhs_t.translation.distance(transform.translation),
+                    rhs_t.translation.distance(transform.translation),
                 )
             })
-            .map(|t| t.translation)
-        {
-            print!("{}", food_position.distance(transform.translation));
-            // 🍎 Mode nourriture : Cherche la nourriture la plus proche
-            (Some(food_position), creature.eat_accel_factor, CreatureState::SeekingFood)
-        } else {
-            // print!("{}", food_position.distance(transform.translation));
-            // 🚶‍♂️ Mode neutre : Avance tout droit
-            (
-                Some(transform.translation),
-                creature.neutral_accel_factor,
-                CreatureState::Neutral
-                ,
-            )
-        };
-        
-        // Mettre à jour l'état de la créature
-        creature_state.state = state;
-        
-        
-
-        if let Some(target_position) = target {
-            accel.0 = (target_position - transform.translation)
-                .normalize_or_zero()
-                .xy()
-                * accel_factor;
-        }
+            .map(|(other_t, other_velocity, other_creature, other_collider, _)| PerceivedCreature {
+                position: other_t.translation.xy(),
+                velocity: other_velocity.0,
+                radius: other_collider.radius,
+                energy_ratio: other_creature.energy / other_creature.max_energy,
+                attack_power_estimate: other_collider.radius * ATTACK_POWER_PER_RADIUS,
+            });
+
+        // 👥 Voisins à éviter
+        perception.neighbours.clear();
+        perception.neighbours.extend(
+            other_creature_query
+                .iter()
+                .filter(|(other_t, _, _, _, _)| {
+                    other_t.translation.distance(transform.translation) <= creature.creature_vision_radius
+                    && other_t.translation != transform.translation
+                })
+                .map(|(other_t, _, _, other_collider, _)| (other_t.translation.xy(), other_collider.radius)),
+        );
 
-        // Limiter la vitesse de la créature
-        if velocity.0.length() > creature.upper_speed {
-            velocity.0 = velocity.0.normalize() * creature.upper_speed;
-        }
+        // 🍎 Nourriture la plus intéressante : la distance est pondérée par ce que le régime permet d'en tirer
+        // Les cadavres et les œufs des autres sont de la viande, ses propres œufs ne se mangent pas
+        perception.nearest_food = food_query
+            .iter()
+            .filter(|(_, _, egg)| !egg.is_some_and(|egg| egg.is_laid_by(creature_entity)))
+            .map(|(t, is_corpse, egg)| (t, is_corpse || egg.is_some()))
+            .filter(|(t, is_meat)| {
+                t.translation.distance(transform.translation) <= creature.food_vision_radius
+                && creature.diet_efficiency(*is_meat) >= MIN_DIET_EFFICIENCY
+            })
+            .min_by(|(lhs_t, lhs_is_meat), (rhs_t, rhs_is_meat)| {
+                order_float(
+                    lhs_t.translation.distance(transform.translation) / creature.diet_efficiency(*lhs_is_meat),
+                    rhs_t.translation.distance(transform.translation) / creature.diet_efficiency(*rhs_is_meat),
+                )
+            })
+            .map(|(t, _)| t.translation.xy());
     }
 }
 
-fn collision_interaction(
+// 🧡 La reproduction ne se déclenche qu'une fois, au début du contact
+fn mate(
     mut commands: Commands,
-    mut events: EventReader<CollisionEvent>,
-    mut creatures: Query<(&mut Creature, &mut CreatureAllies)>,
+    mut started_events: EventReader<CollisionStarted>,
+    mut creatures: Query<(&mut Creature, &mut CreatureAllies, &Controller, &Collider)>,
     transforms: Query<&Transform>,
-    asset_server: Res<AssetServer>,
+    assets: Res<CreatureAssets>,
+    mut innovations: ResMut<InnovationRegistry>,
+    reproduction: Res<Reproduction>,
 ) {
-    for event in events.read() {
-        let Ok([(mut creature_a, mut allies_a), (mut creature_b, mut allies_b)]) = creatures.get_many_mut([event.entity_a, event.entity_b]) else { continue; };
+    for event in started_events.read() {
+        let Ok([(mut creature_a, mut allies_a, controller_a, collider_a), (mut creature_b, mut allies_b, controller_b, _)]) = creatures.get_many_mut([event.entity_a, event.entity_b]) else { continue; };
 
-        // Vérifie si les deux créatures sont en mode "love"
-        if creature_a.is_in_love && creature_b.is_in_love {
+        // Vérifie si les deux créatures sont en mode "love" et peuvent s'accoupler
+        if creature_a.is_in_love && creature_b.is_in_love && reproduction.are_compatible(&creature_a, &creature_b) {
             let Ok(transform_a) = transforms.get(event.entity_a) else { continue; };
             let Ok(transform_b) = transforms.get(event.entity_b) else { continue; };
 
-            let image = asset_server.load("sprites/creature.png");
-
-            let font = asset_server.load("fonts/COOPBL.TTF");
-
-            let baby_max_energy = (creature_a.max_energy + creature_b.max_energy) / 2.0;
-
-            // Apparition du bébé
-            let baby_entity = spawn_creature(
-                ((transform_a.translation.x + transform_b.translation.x) / 2.0) as i32,
-                ((transform_a.translation.y + transform_b.translation.y) / 2.0) as i32,
-                (creature_a.energy + creature_b.energy) / 2.0,
-                baby_max_energy,
-                (creature_a.neutral_accel_factor + creature_b.neutral_accel_factor) / 2.0,
-                (creature_a.eat_accel_factor + creature_b.eat_accel_factor) / 2.0,
-                (creature_a.attack_accel_factor + creature_b.attack_accel_factor) / 2.0,
-                (creature_a.flee_accel_factor + creature_b.flee_accel_factor) / 2.0,
-                (creature_a.love_accel_factor + creature_b.love_accel_factor) / 2.0,
-                (creature_a.upper_speed + creature_b.upper_speed) / 2.0,
-                (creature_a.aggressiveness + creature_b.aggressiveness) / 2.0,
-                (creature_a.attack_power + creature_b.attack_power) / 2.0,
-                (creature_a.hitbox_radius + creature_b.hitbox_radius) / 2.0,
-                (creature_a.hitbox_repulsion + creature_b.hitbox_repulsion) / 2.0,
-                (creature_a.creature_vision_radius + creature_b.creature_vision_radius) / 2.0,
-                (creature_a.food_vision_radius + creature_b.food_vision_radius) / 2.0,
-                (creature_a.love_vision_radius + creature_b.love_vision_radius) / 2.0,
-                (creature_a.want_to_love_rate + creature_b.want_to_love_rate) / 2.0,
-                (creature_a.energy_rate_given_to_baby + creature_b.energy_rate_given_to_baby) / 2.0,
-                (creature_a.is_a_child_time + creature_b.is_a_child_time) / 2.0,
-                u32::max(creature_a.generation, creature_b.generation) + 1,
-                Some((event.entity_a, event.entity_b)),
-                &mut commands,
-                image,
-                font,
-            );
-
-            // Les parents perdent de l'énergie
-            creature_a.energy -= creature_a.energy_rate_given_to_baby * creature_a.energy;
-            creature_b.energy -= creature_b.energy_rate_given_to_baby * creature_b.energy;
+            let mut rng = rand::rng();
+            let position = (transform_a.translation + transform_b.translation) / 2.0;
+            let parents = (event.entity_a, event.entity_b);
+
+            // 👪 Le parent le plus attentionné s'occupe des petits
+            let guardian = if creature_a.parental_care >= creature_b.parental_care { event.entity_a } else { event.entity_b };
+
+            // 🐣 L'énergie donnée par les deux parents est partagée entre les petits de la portée
+            let given_energy_a = creature_a.given_energy(reproduction.investment_factor(&creature_a), reproduction.birth_mode);
+            let given_energy_b = creature_b.given_energy(reproduction.investment_factor(&creature_b), reproduction.birth_mode);
+            let litter_count = ((creature_a.litter_size + creature_b.litter_size) / 2.).round().max(1.) as usize;
+            let baby_energy = (given_energy_a + given_energy_b) / litter_count as f32;
+            let mut received_energy = 0.;
+            let mut shell_energy = 0.;
+
+            for _ in 0..litter_count {
+                // Chaque petit a son propre croisement et ses propres mutations
+                let mut genome = Creature::crossover(&creature_a, &creature_b, baby_energy, &mut rng);
+                genome.mutate(&mut rng);
+                genome.energy = genome.energy.min(genome.max_energy);
+                received_energy += genome.energy;
+                let controller = Controller::inherit(
+                    controller_a,
+                    controller_b,
+                    creature_a.energy >= creature_b.energy,
+                    &mut innovations,
+                    &mut rng,
+                );
+
+                // 🥚 La coquille s'ajoute à ce que paient les parents
+                if reproduction.birth_mode == BirthMode::Egg {
+                    shell_energy += genome.egg_shell_energy();
+                }
+                let embryo = Embryo { genome, controller, parents, guardian };
+                let baby_position = position + litter_offset(collider_a.radius, &mut rng);
+                if let Some(baby_entity) = give_birth(embryo, baby_position, reproduction.birth_mode, &mut commands, &assets) {
+                    allies_a.allies.insert(baby_entity);
+                    allies_b.allies.insert(baby_entity);
+                }
+            }
+
+            let (cost_a, cost_b) = split_litter_cost(received_energy + shell_energy, given_energy_a, given_energy_b);
+            creature_a.energy -= cost_a;
+            creature_b.energy -= cost_b;
             allies_a.allies.insert(event.entity_b);
             allies_b.allies.insert(event.entity_a);
-            allies_a.allies.insert(baby_entity);
-            allies_b.allies.insert(baby_entity);
             creature_a.is_in_love = false;
             creature_b.is_in_love = false;
+            commands.entity(event.entity_a).insert(RefractoryPeriod::new(creature_a.refractory_period));
+            commands.entity(event.entity_b).insert(RefractoryPeriod::new(creature_b.refractory_period));
         }
-        else {
-            // Vérifier si les créatures sont la leur liste d'alliés
-            if allies_a.allies.contains(&event.entity_b) && allies_b.allies.contains(&event.entity_a) {
-                continue;
+    }
+}
+
+// 🔥 Le combat dure tant que le contact dure : chaque créature mord dès que sa morsure est rechargée
+fn fight(
+    mut commands: Commands,
+    contacts: Res<Contacts>,
+    mut creatures: Query<(&mut Creature, &CreatureAllies, &mut BiteCooldown, &Age, &Collider)>,
+    transforms: Query<&Transform>,
+    mut deaths: EventWriter<CreatureDied>,
+    time: Res<time::Time>,
+) {
+    for (_, _, mut bite_cooldown, _, _) in creatures.iter_mut() {
+        bite_cooldown.timer.tick(time.delta());
+    }
+
+    // La vitesse de rapprochement est mesurée par resolve_collisions avant que le choc ne sépare les créatures
+    for (entity_a, entity_b, approach_speed) in contacts.iter() {
+        let Ok([(mut creature_a, allies_a, mut bite_cooldown_a, age_a, collider_a), (mut creature_b, allies_b, mut bite_cooldown_b, age_b, collider_b)]) = creatures.get_many_mut([entity_a, entity_b]) else { continue; };
+        let Ok([transform_a, transform_b]) = transforms.get_many([entity_a, entity_b]) else { continue; };
+
+        if creature_a.is_in_love && creature_b.is_in_love {
+            continue;
+        }
+        // Une créature déjà tuée cette frame ne se bat plus
+        if creature_a.energy <= 0. || creature_b.energy <= 0. {
+            continue;
+        }
+        // Vérifier si les créatures sont la leur liste d'alliés
+        if allies_a.allies.contains(&entity_b) && allies_b.allies.contains(&entity_a) {
+            continue;
+        }
+
+        // 👴 Les créatures âgées mordent moins fort
+        if bite_cooldown_a.timer.finished() {
+            creature_b.energy -= age_a.vigor(&creature_a) * bite_damage(&creature_a, collider_a.radius, &creature_b, collider_b.radius, approach_speed);
+            bite_cooldown_a.timer.reset();
+        }
+        if bite_cooldown_b.timer.finished() {
+            creature_a.energy -= age_b.vigor(&creature_b) * bite_damage(&creature_b, collider_b.radius, &creature_a, collider_a.radius, approach_speed);
+            bite_cooldown_b.timer.reset();
+        }
+
+        // Le tueur mange une partie du corps de sa victime, le reste devient un cadavre
+        if creature_a.energy <= 0. {
+            creature_b.energy = (creature_b.energy + creature_b.diet_efficiency(true) * KILL_ENERGY_RATE * creature_a.body_energy(collider_a.radius)).min(creature_b.max_energy);
+            let corpse_energy = (1. - KILL_ENERGY_RATE) * creature_a.body_energy(collider_a.radius);
+            die(&mut commands, &mut deaths, entity_a, CreatureDied { cause: DeathCause::Killed, position: transform_a.translation, radius: collider_a.radius, corpse_energy });
+        }
+        if creature_b.energy <= 0. {
+            creature_a.energy = (creature_a.energy + creature_a.diet_efficiency(true) * KILL_ENERGY_RATE * creature_b.body_energy(collider_b.radius)).min(creature_a.max_energy);
+            let corpse_energy = (1. - KILL_ENERGY_RATE) * creature_b.body_energy(collider_b.radius);
+            die(&mut commands, &mut deaths, entity_b, CreatureDied { cause: DeathCause::Killed, position: transform_b.translation, radius: collider_b.radius, corpse_energy });
+        }
+    }
+}
+
+
+// 🦷 Quand le contact cesse, le combat s'arrête : la morsure doit se recharger avant la prochaine charge
+fn reset_bites(mut events: EventReader<CollisionEnded>, mut bite_cooldowns: Query<&mut BiteCooldown>) {
+    for event in events.read() {
+        for entity in [event.entity_a, event.entity_b] {
+            if let Ok(mut bite_cooldown) = bite_cooldowns.get_mut(entity) {
+                bite_cooldown.timer.reset();
             }
-            creature_a.energy -= creature_b.attack_power;
-            creature_b.energy -= creature_a.attack_power;
-            if creature_a.energy <= 0. {
-                creature_b.energy = creature_b.max_energy;
-                commands.entity(event.entity_a).despawn_recursive();
+        }
+    }
+}
+
+// Le petit naît directement, ou dans un œuf qui éclora plus tard : renvoie le petit s'il est déjà né
+fn give_birth(embryo: Embryo, position: Vec3, birth_mode: BirthMode, commands: &mut Commands, assets: &CreatureAssets) -> Option<Entity> {
+    match birth_mode {
+        BirthMode::Live => Some(spawn_newborn(embryo, position.xy(), commands, assets)),
+        BirthMode::Egg => {
+            lay_egg(commands, position, embryo, assets.image.clone());
+            None
+        }
+    }
+}
+
+// 👶 Apparition du bébé, confié au parent qui s'en occupe
+pub fn spawn_newborn(embryo: Embryo, position: Vec2, commands: &mut Commands, assets: &CreatureAssets) -> Entity {
+    let baby_entity = spawn_creature(embryo.genome, position, Some(embryo.parents), embryo.controller, commands, assets);
+    commands.entity(baby_entity).insert(Guardian(embryo.guardian));
+    baby_entity
+}
+
+// 🌱 Bourgeonnement : une créature asexuée prête à se reproduire fait seule une copie mutée d'elle-même
+fn reproduce_asexually(
+    mut commands: Commands,
+    mut creatures: Query<(Entity, &mut Creature, &mut CreatureAllies, &Controller, &Transform, &Collider)>,
+    assets: Res<CreatureAssets>,
+    mut innovations: ResMut<InnovationRegistry>,
+    reproduction: Res<Reproduction>,
+) {
+    let mut rng = rand::rng();
+
+    for (entity, mut creature, mut allies, controller, transform, collider) in creatures.iter_mut() {
+        if !creature.is_in_love || !reproduction.is_asexual(&creature) {
+            continue;
+        }
+
+        // 🐣 L'énergie donnée est partagée entre les petits de la portée
+        let given_energy = creature.given_energy(1., reproduction.birth_mode);
+        let litter_count = creature.litter_count();
+        let mut received_energy = 0.;
+
+        for _ in 0..litter_count {
+            let mut genome = Creature {
+                energy: given_energy / litter_count as f32,
+                is_in_love: false,
+                generation: creature.generation + 1,
+                ..creature.clone()
+            };
+            genome.mutate(&mut rng);
+            genome.energy = genome.energy.min(genome.max_energy);
+            received_energy += genome.energy;
+            // Un seul parent : se croiser avec soi-même revient à copier puis muter
+            let controller = Controller::inherit(controller, controller, true, &mut innovations, &mut rng);
+
+            if reproduction.birth_mode == BirthMode::Egg {
+                creature.energy -= genome.egg_shell_energy();
             }
-            if creature_b.energy <= 0. {
-                creature_a.energy = creature_a.max_energy;
-                commands.entity(event.entity_b).despawn_recursive();
+            let embryo = Embryo { genome, controller, parents: (entity, entity), guardian: entity };
+            let position = transform.translation + litter_offset(collider.radius, &mut rng);
+            if let Some(baby_entity) = give_birth(embryo, position, reproduction.birth_mode, &mut commands, &assets) {
+                allies.allies.insert(baby_entity);
             }
         }
+
+        creature.energy -= received_energy;
+        creature.is_in_love = false;
+        commands.entity(entity).insert(RefractoryPeriod::new(creature.refractory_period));
+    }
+}
+
+// Les parents ne perdent que l'énergie que les petits ont reçue, et les coquilles, chacun au prorata de son don
+fn split_litter_cost(litter_cost: f32, given_energy_a: f32, given_energy_b: f32) -> (f32, f32) {
+    let given_energy = (given_energy_a + given_energy_b).max(f32::EPSILON);
+    (litter_cost * given_energy_a / given_energy, litter_cost * given_energy_b / given_energy)
+}
+
+// Les petits d'une même portée naissent autour du parent
+fn litter_offset(radius: f32, rng: &mut impl Rng) -> Vec3 {
+    (Vec2::from_angle(rng.random_range(-PI..PI)) * radius).extend(0.)
+}
+
+fn blend_gene(gene_a: f32, gene_b: f32, rng: &mut impl Rng) -> f32 {
+    gene_a + (gene_b - gene_a) * rng.random::<f32>()
+}
+
+fn mutate_gene(gene: &mut f32, min: f32, max: f32, rng: &mut impl Rng) {
+    if rng.random_bool(GENE_MUTATION_RATE) {
+        *gene = (*gene + rng.random_range(-1.0..1.0) * GENE_MUTATION_STRENGTH * (max - min)).clamp(min, max);
+    }
+}
+
+// 💀 La créature disparaît, leave_corpses la remplace par un cadavre
+fn die(commands: &mut Commands, deaths: &mut EventWriter<CreatureDied>, entity: Entity, death: CreatureDied) {
+    commands.entity(entity).despawn_recursive();
+    deaths.send(death);
+}
+
+// 🦴 Chaque créature morte laisse un cadavre pour les charognards
+fn leave_corpses(mut commands: Commands, mut events: EventReader<CreatureDied>, assets: Res<CreatureAssets>) {
+    for event in events.read() {
+        spawn_corpse(&mut commands, event.position, event.corpse_energy, event.radius, assets.image.clone());
+    }
+}
+
+fn record_deaths(mut events: EventReader<CreatureDied>, mut stats: ResMut<DeathStats>) {
+    for event in events.read() {
+        match event.cause {
+            DeathCause::Starvation => stats.starvation += 1,
+            DeathCause::Killed => stats.killed += 1,
+            DeathCause::OldAge => stats.old_age += 1,
+        }
+        info!("💀 {:?} (faim : {}, combat : {}, vieillesse : {})", event.cause, stats.starvation, stats.killed, stats.old_age);
     }
 }
 
+// 🦷 Une morsure est plus forte si l'attaquant est plus gros (à sa taille actuelle) et arrive vite, plus faible contre une armure
+fn bite_damage(attacker: &Creature, attacker_radius: f32, defender: &Creature, defender_radius: f32, approach_speed: f32) -> f32 {
+    let size_advantage = (attacker_radius / defender_radius)
+        .powf(SIZE_ADVANTAGE_EXPONENT)
+        .clamp(1. / MAX_SIZE_ADVANTAGE, MAX_SIZE_ADVANTAGE);
+    let charge_bonus = 1. + approach_speed.max(0.) / CHARGE_SPEED_FOR_DOUBLE_DAMAGE;
+    let armor_reduction = ARMOR_HALF_REDUCTION / (ARMOR_HALF_REDUCTION + defender.armor);
 
+    attacker.attack_power * BITE_DAMAGE * size_advantage * charge_bonus * armor_reduction
+}
 
+// La nourriture a un collider capteur : on la mange quand il commence à toucher une créature
 fn eat_food(
-    food_query: Query<(&Transform, &Food, Entity)>,
-    mut creature_query: Query<(&Transform, &mut Creature)>,
+    mut events: EventReader<CollisionStarted>,
+    food_query: Query<(&Food, Has<Corpse>, Option<&Egg>)>,
+    mut creature_query: Query<(&mut Creature, &Transform)>,
+    ward_query: Query<(Entity, &Guardian)>,
     mut commands: Commands,
 ) {
-    for (food_transform, food, food_entity) in food_query.iter() {
-        let Some((creature_transform, mut closest_creature)) =
-            creature_query.iter_mut().min_by(|lhs, rhs| {
-                order_float(
-                    lhs.0.translation.distance(food_transform.translation),
-                    rhs.0.translation.distance(food_transform.translation),
-                )
-            })
-        else {
-            break;
+    let mut eaten_foods = HashSet::new();
+    let mut rng = rand::rng();
+
+    for event in events.read() {
+        let (food_entity, creature_entity) = if food_query.contains(event.entity_a) {
+            (event.entity_a, event.entity_b)
+        } else {
+            (event.entity_b, event.entity_a)
         };
-        if creature_transform
-            .translation
-            .distance(food_transform.translation)
-            > closest_creature.hitbox_radius
-        {
+        let Ok((food, is_corpse, egg)) = food_query.get(food_entity) else { continue; };
+        let Ok((creature, transform)) = creature_query.get(creature_entity) else { continue; };
+        let efficiency = creature.diet_efficiency(is_corpse || egg.is_some());
+        if efficiency < MIN_DIET_EFFICIENCY || eaten_foods.contains(&food_entity) {
             continue;
         }
-
-        closest_creature.energy = (closest_creature.energy + food.energy).clamp(0., closest_creature.max_energy);
+        // 🥚 Les parents ne mangent pas leurs œufs, et la coquille résiste parfois
+        if egg.is_some_and(|egg| egg.is_laid_by(creature_entity) || !rng.random_bool(egg.embryo.genome.egg_vulnerability as f64)) {
+            continue;
+        }
+        eaten_foods.insert(food_entity);
+        let position = transform.translation;
+        let gained_energy = efficiency * food.energy;
         commands.entity(food_entity).despawn();
+
+        // 👪 Le parent partage sa nourriture avec ses petits proches
+        let wards: Vec<Entity> = ward_query
+            .iter()
+            .filter(|(_, guardian)| guardian.0 == creature_entity)
+            .map(|(ward, _)| ward)
+            .filter(|ward| {
+                creature_query
+                    .get(*ward)
+                    .is_ok_and(|(_, ward_t)| ward_t.translation.distance(position) <= FOOD_SHARING_RADIUS)
+            })
+            .collect();
+        let Ok((mut creature, _)) = creature_query.get_mut(creature_entity) else { continue; };
+        let shared_energy = if wards.is_empty() { 0. } else { creature.parental_care * gained_energy };
+        creature.energy = (creature.energy + gained_energy - shared_energy).clamp(0., creature.max_energy);
+
+        for ward in &wards {
+            let Ok((mut ward_creature, _)) = creature_query.get_mut(*ward) else { continue; };
+            ward_creature.energy = (ward_creature.energy + shared_energy / wards.len() as f32).min(ward_creature.max_energy);
+        }
     }
 }
 
@@ -541,16 +1040,43 @@ pub fn update_energy_bars(
     }
 }
 
+type TiredCreatureData = (
+    &'static mut Creature,
+    &'static Transform,
+    &'static Collider,
+    &'static Velocity,
+    &'static Mass,
+    &'static Age,
+    &'static CreatureStateComponent,
+    Entity,
+    Has<BabyCreature>,
+    Has<RefractoryPeriod>,
+);
+
 fn get_tired(
-    mut creature_query: Query<(&mut Creature, &Velocity, Entity, Option<&BabyCreature>)>,
+    mut creature_query: Query<TiredCreatureData>,
     mut commands: Commands,
     time: Res<time::Time>,
+    metabolism: Res<Metabolism>,
+    mut deaths: EventWriter<CreatureDied>,
 ) {
-    for (mut creature, velocity, creature_entity, baby_creature) in creature_query.iter_mut() {
-        creature.energy -= (velocity.0.length() + PASSIVE_ENERGY_LOSS) * time.delta_secs() ;
+    for (mut creature, transform, collider, velocity, mass, age, creature_state, creature_entity, is_baby, is_refractory) in creature_query.iter_mut() {
+        // 🔋 Métabolisme de base selon les traits (plus coûteux avec l'âge), plus le coût du mouvement
+        let mut energy_rate = age.metabolism_factor(&creature) * metabolism.basal_rate(&creature, mass.0)
+            + metabolism.movement_rate(mass.0, velocity.0.length());
+
+        // 🦷 Les morsures fatiguent pendant une attaque
+        if creature_state.state == CreatureState::Attacking {
+            energy_rate += metabolism.bite_rate(&creature);
+        }
+        // 🌱 Grandir coûte de l'énergie
+        if is_baby {
+            energy_rate += metabolism.growth_rate(&creature);
+        }
+        creature.energy -= energy_rate * time.delta_secs();
 
-        //Vérifier si l'entité possède babyCreature
-        if let Some(_baby_creature) = baby_creature {
+        //Vérifier si l'entité possède babyCreature ou se remet d'une portée
+        if is_baby || is_refractory {
             creature.is_in_love = false;
         }
         else {
@@ -558,15 +1084,96 @@ fn get_tired(
         }
 
         if creature.energy <= 0. {
-            commands.entity(creature_entity).despawn_recursive();
+            let corpse_energy = creature.body_energy(collider.radius);
+            die(&mut commands, &mut deaths, creature_entity, CreatureDied { cause: DeathCause::Starvation, position: transform.translation, radius: collider.radius, corpse_energy });
+        } else if age.seconds >= creature.lifespan {
+            // ⏳ Morte de vieillesse : le corps entier reste pour les charognards
+            let corpse_energy = creature.body_energy(collider.radius);
+            creature.energy = 0.; // Ne se bat plus cette frame
+            die(&mut commands, &mut deaths, creature_entity, CreatureDied { cause: DeathCause::OldAge, position: transform.translation, radius: collider.radius, corpse_energy });
         }
     }
 }
 
-fn order_float(lhs: f32, rhs: f32) -> Ordering {
+pub fn order_float(lhs: f32, rhs: f32) -> Ordering {
     match (lhs, rhs) {
         _ if lhs < rhs => Ordering::Less,
         _ if lhs > rhs => Ordering::Greater,
         _ => Ordering::Equal,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent(energy: f32) -> Creature {
+        Creature {
+            energy,
+            max_energy: MAX_CREATURE_ENERGY,
+            energy_rate_given_to_baby: MAX_ENERGY_RATE_GIVEN_TO_BABY,
+            egg_vulnerability: 0.,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn parental_care_reduces_given_energy() {
+        let careless = parent(1000.);
+        let caring = Creature { parental_care: 0.5, ..parent(1000.) };
+
+        let given = careless.given_energy(1., BirthMode::Live);
+
+        assert!((caring.given_energy(1., BirthMode::Live) - given / 2.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn given_energy_leaves_room_for_shells_only_when_eggs_are_laid() {
+        // Avec deux sexes, la femelle donnerait plus que toute son énergie sans plafond
+        let mother = parent(1000.);
+        let investment_factor = FEMALE_INVESTMENT_FACTOR;
+
+        let live = mother.given_energy(investment_factor, BirthMode::Live);
+        let egg = mother.given_energy(investment_factor, BirthMode::Egg);
+
+        assert!((live - MAX_GIVEN_ENERGY_RATE * mother.energy).abs() < 1e-3);
+        assert!((egg - MAX_GIVEN_ENERGY_RATE * mother.energy / (1. + EGG_SHELL_ENERGY_RATE)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn litter_with_shells_never_costs_more_than_parents_can_give() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mother = parent(1000.);
+        let father = parent(3000.);
+        let given_energy_a = mother.given_energy(FEMALE_INVESTMENT_FACTOR, BirthMode::Egg);
+        let given_energy_b = father.given_energy(MALE_INVESTMENT_FACTOR, BirthMode::Egg);
+        let litter_count = 3;
+
+        let mut litter_cost = 0.;
+        for _ in 0..litter_count {
+            let genome = Creature::crossover(&mother, &father, (given_energy_a + given_energy_b) / litter_count as f32, &mut rng);
+            litter_cost += genome.energy + genome.egg_shell_energy();
+        }
+        let (cost_a, cost_b) = split_litter_cost(litter_cost, given_energy_a, given_energy_b);
+
+        assert!((cost_a + cost_b - litter_cost).abs() < 1e-2);
+        assert!(cost_a <= MAX_GIVEN_ENERGY_RATE * mother.energy + 1e-2);
+        assert!(cost_b <= MAX_GIVEN_ENERGY_RATE * father.energy + 1e-2);
+    }
+
+    #[test]
+    fn parents_only_pay_what_capped_babies_receive() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let parent_a = Creature { max_energy: 100., ..parent(5000.) };
+        let parent_b = Creature { max_energy: 100., ..parent(5000.) };
+        let given_energy_a = parent_a.given_energy(1., BirthMode::Live);
+        let given_energy_b = parent_b.given_energy(1., BirthMode::Live);
+
+        let mut genome = Creature::crossover(&parent_a, &parent_b, given_energy_a + given_energy_b, &mut rng);
+        genome.energy = genome.energy.min(genome.max_energy);
+        let (cost_a, cost_b) = split_litter_cost(genome.energy, given_energy_a, given_energy_b);
+
+        assert!((cost_a - 50.).abs() < 1e-3);
+        assert!((cost_b - 50.).abs() < 1e-3);
+    }
+}
@@ -1,6 +1,17 @@
 use bevy::prelude::*;
 
-use crate::creature::{CreatureState, CreatureStateComponent};
+use crate::collider::Collider;
+use crate::creature::{Creature, CreatureState, CreatureStateComponent};
+use crate::rigidbody::{Mass, MaxSpeed};
+
+// Taille à la naissance, en part de la taille génétique
+pub const BIRTH_SIZE_RATE: f32 = 0.3;
+
+// Part de la durée de vie à partir de laquelle la créature vieillit
+const ELDER_AGE_RATE: f32 = 0.7;
+// En fin de vie, la vitesse et l'attaque sont réduites de moitié et le métabolisme doublé
+const SENESCENCE_MAX_DECLINE: f32 = 0.5;
+const SENESCENCE_MAX_METABOLISM_INCREASE: f32 = 1.;
 
 pub struct CreatureStatePlugin;
 
@@ -8,6 +19,8 @@ pub struct CreatureStatePlugin;
 impl Plugin for CreatureStatePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Update, update_baby_status)
+            .add_systems(Update, (update_age, update_growth).chain())
+            .add_systems(Update, update_refractory_period)
             .add_systems(Update, update_state_indicator);
     }
 }
@@ -21,6 +34,63 @@ pub struct BabyCreature {
 #[derive(Component)]
 pub struct BabyIndicator;
 
+// 🕑 Après une portée, la créature ne peut plus se reproduire pendant un moment
+#[derive(Component)]
+pub struct RefractoryPeriod {
+    pub timer: Timer,
+}
+
+impl RefractoryPeriod {
+    pub fn new(seconds: f32) -> Self {
+        RefractoryPeriod {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+}
+
+// 👪 Parent que le petit suit, et qui le nourrit et le défend, jusqu'à la fin de l'enfance
+#[derive(Component)]
+pub struct Guardian(pub Entity);
+
+// ⏳ Âge en secondes : juvénile tant que BabyCreature est là, puis adulte, puis âgée avec ElderCreature
+#[derive(Component, Debug, Clone, Default)]
+pub struct Age {
+    pub seconds: f32,
+}
+
+impl Age {
+    pub fn elder_age(creature: &Creature) -> f32 {
+        (ELDER_AGE_RATE * creature.lifespan).max(creature.is_a_child_time)
+    }
+
+    // 0 avant la vieillesse, 1 à la fin de la durée de vie
+    pub fn senescence(&self, creature: &Creature) -> f32 {
+        let elder_age = Age::elder_age(creature);
+        ((self.seconds - elder_age) / (creature.lifespan - elder_age).max(f32::EPSILON)).clamp(0., 1.)
+    }
+
+    // 🌱 Part de la taille génétique atteinte : grandit pendant toute l'enfance
+    pub fn growth(&self, creature: &Creature) -> f32 {
+        BIRTH_SIZE_RATE + (1. - BIRTH_SIZE_RATE) * (self.seconds / creature.is_a_child_time).min(1.)
+    }
+
+    // Multiplie la vitesse et l'attaque : le juvénile grandit, l'âgée décline
+    pub fn vigor(&self, creature: &Creature) -> f32 {
+        self.growth(creature) * (1. - SENESCENCE_MAX_DECLINE * self.senescence(creature))
+    }
+
+    // Multiplie le métabolisme de base
+    pub fn metabolism_factor(&self, creature: &Creature) -> f32 {
+        1. + SENESCENCE_MAX_METABOLISM_INCREASE * self.senescence(creature)
+    }
+}
+
+#[derive(Component)]
+pub struct ElderCreature;
+
+#[derive(Component)]
+pub struct ElderIndicator;
+
 #[derive(Component)]
 pub struct CreatureStateIndicator;
 
@@ -34,7 +104,7 @@ fn update_baby_status(
         baby.lifetime.tick(time.delta());
 
         if baby.lifetime.finished() {
-            commands.entity(entity).remove::<BabyCreature>(); // Enlève le statut bébé
+            commands.entity(entity).remove::<(BabyCreature, Guardian)>(); // Enlève le statut bébé
 
             // Supprime l’indicateur visuel
             for &child in children.iter() {
@@ -46,20 +116,79 @@ fn update_baby_status(
     }
 }
 
+fn update_refractory_period(
+    mut commands: Commands,
+    mut creatures: Query<(Entity, &mut RefractoryPeriod)>,
+    time: Res<Time>,
+) {
+    for (entity, mut refractory_period) in creatures.iter_mut() {
+        refractory_period.timer.tick(time.delta());
+
+        if refractory_period.timer.finished() {
+            commands.entity(entity).remove::<RefractoryPeriod>();
+        }
+    }
+}
+
+pub fn update_age(
+    mut commands: Commands,
+    mut creatures: Query<(Entity, &Creature, &mut Age, &mut MaxSpeed, Has<ElderCreature>)>,
+    time: Res<Time>,
+) {
+    for (entity, creature, mut age, mut max_speed, is_elder) in creatures.iter_mut() {
+        age.seconds += time.delta_secs();
+        max_speed.0 = creature.upper_speed * age.vigor(creature);
+
+        if !is_elder && age.seconds >= Age::elder_age(creature) {
+            commands.entity(entity).insert(ElderCreature).with_children(|parent| {
+                // 👴 Indicateur vieillesse, à la place de l'indicateur bébé
+                parent.spawn((
+                    Sprite {
+                        color: Color::srgb(0.6, 0.6, 0.6),
+                        custom_size: Some(Vec2::new(10.0, 10.0)),
+                        ..default()
+                    },
+                    Transform {
+                        translation: Vec3::new(10.0, -10.0, 1.0),
+                        ..default()
+                    },
+                    ElderIndicator,
+                ));
+            });
+        }
+    }
+}
+
+// Le corps suit la croissance : collider, masse et sprite
+fn update_growth(mut creatures: Query<(&Creature, &Age, &mut Collider, &mut Mass, &mut Sprite)>) {
+    for (creature, age, mut collider, mut mass, mut sprite) in creatures.iter_mut() {
+        let radius = creature.hitbox_radius * age.growth(creature);
+        if collider.radius == radius {
+            continue;
+        }
+
+        collider.radius = radius;
+        *mass = Mass::from_radius(radius);
+        sprite.custom_size = Some(Vec2::splat(radius * 2.0));
+    }
+}
+
 fn update_state_indicator(
-    creature_query: Query<(&CreatureStateComponent, &Children)>,
+    creature_query: Query<(&CreatureStateComponent, &Children, Has<RefractoryPeriod>)>,
     mut indicator_query: Query<&mut Sprite, With<CreatureStateIndicator>>,
 ) {
-    for (creature_state, children) in creature_query.iter() {
+    for (creature_state, children, is_refractory) in creature_query.iter() {
         for &child in children.iter() {
             if let Ok(mut sprite) = indicator_query.get_mut(child) {
-                sprite.color = match creature_state.state {
+                let color = match creature_state.state {
                     CreatureState::Neutral => Color::WHITE,                                      // 🚶‍♂️ Blanc
                     CreatureState::SeekingFood => Color::srgb(0.0, 1.0, 0.0),   // 🍎 Vert
                     CreatureState::InLove => Color::srgb(1.0, 0.75, 0.8),       // 🧡 Rose
                     CreatureState::Attacking => Color::srgb(1.0, 0.0, 0.0),     // 🔥 Rouge
                     CreatureState::Fleeing => Color::srgb(0.0, 0.0, 1.0),       // 🏃‍♂️ Bleu
                 };
+                // 🕑 Indicateur pâle pendant la période réfractaire
+                sprite.color = if is_refractory { color.with_alpha(0.4) } else { color };
             }
         }
     }
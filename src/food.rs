@@ -1,25 +1,76 @@
+use std::f32::consts::PI;
+
 use bevy::prelude::*;
 use rand::prelude::*;
 
+use crate::collider::{Collider, CollisionLayers};
+use crate::reproduction::Egg;
 use crate::MAP_HEIGHT;
 use crate::MAP_WIDTH;
 
 const FOOD_SPAWN_INTERVAL: f32 = 0.02;
+const FOOD_RADIUS: f32 = 5.;
+const FOOD_PATCH_COUNT: usize = 6;
+// Part de l'énergie d'un cadavre perdue par seconde, et énergie sous laquelle il disparaît
+const CORPSE_DECAY_RATE: f32 = 0.05;
+const CORPSE_MIN_ENERGY: f32 = 10.;
 
 pub struct FoodPlugin;
 
-#[derive(Component)]
+#[derive(Component, Default)]
 #[require(Transform)]
 pub struct Food {
     pub energy: f32,
 }
 
+// 💀 Cadavre d'une créature : se mange comme la nourriture, mais pourrit avec le temps
+#[derive(Component)]
+#[require(Food)]
+pub struct Corpse;
+
 #[derive(Resource)]
 struct FoodSpriteHandle(Handle<Image>);
 
 #[derive(Resource)]
 struct FoodSpawnTimer(Timer);
 
+// Où pousse la nourriture végétale
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FoodDistribution {
+    #[default]
+    Uniform,  // 🌍 Partout sur la carte
+    Patches,  // 🌳 Autour de quelques zones fixes, en gaussienne
+    Hotspots, // 🌦️ Comme Patches, mais les zones se déplacent
+}
+
+// 🌱 Réglages de la repousse : elle ralentit à mesure que la carte ou la région se remplit
+#[derive(Resource, Debug, Clone)]
+pub struct FoodEcology {
+    pub distribution: FoodDistribution,
+    pub carrying_capacity: usize,        // Nombre maximal de nourritures végétales sur la carte
+    pub region_size: f32,                // Côté des régions carrées de la carte
+    pub region_carrying_capacity: usize, // Nombre maximal de nourritures végétales par région
+    pub patch_spread: f32,               // Écart type autour du centre d'une zone
+    pub hotspot_speed: f32,              // Vitesse de déplacement des zones mobiles
+}
+
+impl Default for FoodEcology {
+    fn default() -> Self {
+        FoodEcology {
+            distribution: FoodDistribution::Uniform,
+            carrying_capacity: 2000,
+            region_size: 500.,
+            region_carrying_capacity: 60,
+            patch_spread: 200.,
+            hotspot_speed: 50.,
+        }
+    }
+}
+
+// Centre et vitesse de chaque zone de nourriture
+#[derive(Resource, Default)]
+struct FoodPatches(Vec<(Vec2, Vec2)>);
+
 impl Plugin for FoodPlugin {
     fn build(&self, app: &mut App) {
         let food_spawn_timer = FoodSpawnTimer(Timer::from_seconds(
@@ -27,8 +78,12 @@ impl Plugin for FoodPlugin {
             TimerMode::Repeating,
         ));
 
-        app.add_systems(Startup, init_food_sprite_handle)
-            .add_systems(Update, spawn_foods)
+        app.init_resource::<FoodEcology>()
+            .init_resource::<FoodPatches>()
+            .add_systems(Startup, (init_food_sprite_handle, init_food_patches))
+            .add_systems(Update, switch_food_distribution)
+            .add_systems(Update, (move_hotspots, spawn_foods).chain())
+            .add_systems(Update, decay_corpses)
             .insert_resource(food_spawn_timer);
     }
 }
@@ -38,19 +93,99 @@ fn init_food_sprite_handle(mut commands: Commands, asset_server: Res<AssetServer
     commands.insert_resource(FoodSpriteHandle(image));
 }
 
+fn init_food_patches(mut patches: ResMut<FoodPatches>, ecology: Res<FoodEcology>) {
+    let mut rng = rand::rng();
+    patches.0 = (0..FOOD_PATCH_COUNT)
+        .map(|_| {
+            let center = random_map_position(&mut rng);
+            let velocity = Vec2::from_angle(rng.random_range(-PI..PI)) * ecology.hotspot_speed;
+            (center, velocity)
+        })
+        .collect();
+}
+
+fn switch_food_distribution(keyboard_input: Res<ButtonInput<KeyCode>>, mut ecology: ResMut<FoodEcology>) {
+    if keyboard_input.just_pressed(KeyCode::KeyF) {
+        ecology.distribution = match ecology.distribution {
+            FoodDistribution::Uniform => FoodDistribution::Patches,
+            FoodDistribution::Patches => FoodDistribution::Hotspots,
+            FoodDistribution::Hotspots => FoodDistribution::Uniform,
+        };
+    }
+}
+
+// 🌦️ Les zones mobiles rebondissent sur les bords de la carte
+fn move_hotspots(mut patches: ResMut<FoodPatches>, ecology: Res<FoodEcology>, time: Res<Time>) {
+    if ecology.distribution != FoodDistribution::Hotspots {
+        return;
+    }
+
+    let half_map = Vec2::new(MAP_WIDTH as f32, MAP_HEIGHT as f32) / 2.;
+    for (center, velocity) in patches.0.iter_mut() {
+        *center += *velocity * time.delta_secs();
+        if center.x.abs() > half_map.x {
+            velocity.x = -velocity.x;
+        }
+        if center.y.abs() > half_map.y {
+            velocity.y = -velocity.y;
+        }
+        *center = center.clamp(-half_map, half_map);
+    }
+}
+
+fn random_map_position(rng: &mut impl Rng) -> Vec2 {
+    Vec2::new(
+        rng.random_range(-MAP_WIDTH / 2..MAP_WIDTH / 2) as f32,
+        rng.random_range(-MAP_HEIGHT / 2..MAP_HEIGHT / 2) as f32,
+    )
+}
+
+// Gaussienne autour d'une zone tirée au hasard : le rayon suit une loi de Rayleigh
+fn random_patch_position(patches: &FoodPatches, spread: f32, rng: &mut impl Rng) -> Vec2 {
+    let Some((center, _)) = patches.0.choose(rng) else { return random_map_position(rng); };
+    let radius = spread * (-2. * (1. - rng.random::<f32>()).ln()).sqrt();
+    let half_map = Vec2::new(MAP_WIDTH as f32, MAP_HEIGHT as f32) / 2.;
+    (*center + Vec2::from_angle(rng.random_range(-PI..PI)) * radius).clamp(-half_map, half_map)
+}
+
+fn region_of(position: Vec2, region_size: f32) -> IVec2 {
+    (position / region_size).floor().as_ivec2()
+}
+
+// Seule la nourriture végétale compte pour la repousse
+type PlantFoodFilter = (With<Food>, Without<Corpse>, Without<Egg>);
+
 fn spawn_foods(
     mut commands: Commands,
     time: Res<Time>,
     food_sprite_handle: Res<FoodSpriteHandle>,
     mut timer: ResMut<FoodSpawnTimer>,
+    ecology: Res<FoodEcology>,
+    patches: Res<FoodPatches>,
+    plant_foods: Query<&Transform, PlantFoodFilter>,
 ) {
     timer.0.tick(time.delta());
     if !timer.0.finished() {
         return;
     }
     let mut rng = rand::rng();
-    let x = rng.random_range(-MAP_WIDTH / 2..MAP_WIDTH / 2);
-    let y = rng.random_range(-MAP_HEIGHT / 2..MAP_HEIGHT / 2);
+    let position = match ecology.distribution {
+        FoodDistribution::Uniform => random_map_position(&mut rng),
+        FoodDistribution::Patches | FoodDistribution::Hotspots => random_patch_position(&patches, ecology.patch_spread, &mut rng),
+    };
+
+    // 🌱 Repousse logistique : plus la carte et la région sont pleines, moins la nourriture repousse
+    let region = region_of(position, ecology.region_size);
+    let food_count = plant_foods.iter().count();
+    let region_food_count = plant_foods
+        .iter()
+        .filter(|transform| region_of(transform.translation.xy(), ecology.region_size) == region)
+        .count();
+    let global_room = (1. - food_count as f32 / ecology.carrying_capacity.max(1) as f32).max(0.);
+    let region_room = (1. - region_food_count as f32 / ecology.region_carrying_capacity.max(1) as f32).max(0.);
+    if !rng.random_bool((global_room * region_room).clamp(0., 1.) as f64) {
+        return;
+    }
 
     let sprite = Sprite {
         image: food_sprite_handle.0.clone(),
@@ -58,7 +193,48 @@ fn spawn_foods(
     };
     commands.spawn((
         Food { energy: 240. },
-        Transform::from_xyz(x as f32, y as f32, 0.0),
+        Transform::from_translation(position.extend(0.)),
+        Collider {
+            radius: FOOD_RADIUS,
+            restitution: 0.,
+            layer: CollisionLayers::FOOD,
+            mask: CollisionLayers::CREATURE,
+            sensor: true,
+        },
         sprite,
     ));
 }
+
+pub fn spawn_corpse(commands: &mut Commands, position: Vec3, energy: f32, radius: f32, image: Handle<Image>) {
+    commands.spawn((
+        Corpse,
+        Food { energy },
+        Transform::from_translation(position),
+        Collider {
+            radius,
+            restitution: 0.,
+            layer: CollisionLayers::FOOD,
+            mask: CollisionLayers::CREATURE,
+            sensor: true,
+        },
+        Sprite {
+            image,
+            color: Color::srgb(0.3, 0.3, 0.3), // Gris pour les cadavres
+            custom_size: Some(Vec2::new(radius * 2.0, radius * 2.0)),
+            ..default()
+        },
+    ));
+}
+
+fn decay_corpses(
+    mut commands: Commands,
+    mut corpses: Query<(Entity, &mut Food), With<Corpse>>,
+    time: Res<Time>,
+) {
+    for (entity, mut food) in corpses.iter_mut() {
+        food.energy *= (-CORPSE_DECAY_RATE * time.delta_secs()).exp();
+        if food.energy < CORPSE_MIN_ENERGY {
+            commands.entity(entity).despawn();
+        }
+    }
+}
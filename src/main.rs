@@ -1,17 +1,26 @@
 use bevy::prelude::*;
 
+mod brain;
+mod controller;
 mod creature;
 mod creature_state;
 mod food;
+mod metabolism;
 mod player_camera;
+mod reproduction;
 mod rigidbody;
 mod simulation_speed;
+mod steering;
 mod collider;
 
+use brain::BrainPlugin;
+use controller::ControllerPlugin;
 use creature::CreaturePlugin;
 use creature_state::CreatureStatePlugin;
 use food::FoodPlugin;
+use metabolism::MetabolismPlugin;
 use player_camera::PlayerCameraPlugin;
+use reproduction::ReproductionPlugin;
 use rigidbody::RigidbodyPlugin;
 use simulation_speed::SimulationSpeedPlugin;
 use collider::ColliderPlugin;
@@ -23,11 +32,15 @@ fn main() -> AppExit {
     App::new()
         .add_plugins((
             DefaultPlugins,
+            BrainPlugin,
+            ControllerPlugin,
             CreaturePlugin,
             CreatureStatePlugin,
             SimulationSpeedPlugin,
             PlayerCameraPlugin,
             FoodPlugin,
+            MetabolismPlugin,
+            ReproductionPlugin,
             RigidbodyPlugin,
             ColliderPlugin,
         ))
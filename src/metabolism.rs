@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+
+use crate::creature::Creature;
+
+pub struct MetabolismPlugin;
+
+impl Plugin for MetabolismPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Metabolism>();
+    }
+}
+
+// Coefficients du coût énergétique des traits, par seconde
+#[derive(Resource, Debug, Clone)]
+pub struct Metabolism {
+    pub basal_cost: f32,        // Coût fixe de toute créature
+    pub mass_cost: f32,         // Par unité de masse
+    pub vision_cost: f32,       // Par unité de rayon de vision (créatures, nourriture et amour)
+    pub attack_power_cost: f32, // Par point d'attack_power
+    pub max_energy_cost: f32,   // Par unité de max_energy stockable
+    pub armor_cost: f32,        // Par point d'armure
+    pub movement_cost: f32,     // Multiplie masse * vitesse²
+    pub bite_stamina_cost: f32, // Par point d'attack_power et par morsure/seconde, pendant une attaque
+    pub growth_cost: f32,       // Part de l'énergie du corps adulte investie pour grandir pendant l'enfance
+}
+
+impl Default for Metabolism {
+    fn default() -> Self {
+        Metabolism {
+            basal_cost: 20.,
+            mass_cost: 10.,
+            vision_cost: 0.005,
+            attack_power_cost: 0.5,
+            max_energy_cost: 0.004,
+            armor_cost: 0.5,
+            movement_cost: 0.002,
+            bite_stamina_cost: 0.5,
+            growth_cost: 0.2,
+        }
+    }
+}
+
+impl Metabolism {
+    // Ce que coûte le corps de la créature, même immobile
+    pub fn basal_rate(&self, creature: &Creature, mass: f32) -> f32 {
+        self.basal_cost
+            + self.mass_cost * mass
+            + self.vision_cost * (creature.creature_vision_radius + creature.food_vision_radius + creature.love_vision_radius)
+            + self.attack_power_cost * creature.attack_power
+            + self.max_energy_cost * creature.max_energy
+            + self.armor_cost * creature.armor
+    }
+
+    // Énergie cinétique : masse * vitesse²
+    pub fn movement_rate(&self, mass: f32, speed: f32) -> f32 {
+        self.movement_cost * mass * speed * speed
+    }
+
+    pub fn bite_rate(&self, creature: &Creature) -> f32 {
+        self.bite_stamina_cost * creature.attack_power / creature.bite_cooldown
+    }
+
+    // Coût de la croissance, étalé sur toute l'enfance
+    pub fn growth_rate(&self, creature: &Creature) -> f32 {
+        self.growth_cost * creature.body_energy(creature.hitbox_radius) / creature.is_a_child_time
+    }
+}
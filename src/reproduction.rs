@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+
+use crate::collider::{Collider, CollisionLayers};
+use crate::controller::Controller;
+use crate::creature::{spawn_newborn, Creature, CreatureAllies, CreatureAssets};
+use crate::food::Food;
+use crate::rigidbody::PhysicsSet;
+
+const EGG_RADIUS: f32 = 8.;
+// Avec la stratégie héritable, une créature dont l'asexuality dépasse ce seuil bourgeonne seule
+const ASEXUALITY_THRESHOLD: f32 = 0.5;
+
+pub struct ReproductionPlugin;
+
+impl Plugin for ReproductionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Reproduction>()
+            .add_systems(Update, switch_birth_mode)
+            .add_systems(Update, switch_strategy)
+            .add_systems(Update, switch_two_sexes)
+            .add_systems(Update, hatch_eggs.before(PhysicsSet::Collision)); // Un œuf éclos n'est plus mangé : eat_food passe après les collisions
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BirthMode {
+    #[default]
+    Live, // 👶 Le petit naît directement
+    Egg,  // 🥚 Le couple pond un œuf qui éclot après l'incubation
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReproductionStrategy {
+    #[default]
+    Sexual,    // 🧡 Toutes les créatures cherchent un partenaire
+    Asexual,   // 🌱 Toutes les créatures bourgeonnent seules
+    Heritable, // 🧬 Le gène asexuality décide pour chaque créature
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct Reproduction {
+    pub birth_mode: BirthMode,
+    pub strategy: ReproductionStrategy,
+    pub two_sexes: bool, // ⚤ Seuls un mâle et une femelle peuvent s'accoupler
+}
+
+impl Reproduction {
+    pub fn are_compatible(&self, creature_a: &Creature, creature_b: &Creature) -> bool {
+        !self.two_sexes || creature_a.sex != creature_b.sex
+    }
+
+    pub fn investment_factor(&self, creature: &Creature) -> f32 {
+        if self.two_sexes { creature.sex.investment_factor() } else { 1. }
+    }
+
+    pub fn is_asexual(&self, creature: &Creature) -> bool {
+        match self.strategy {
+            ReproductionStrategy::Sexual => false,
+            ReproductionStrategy::Asexual => true,
+            ReproductionStrategy::Heritable => creature.asexuality >= ASEXUALITY_THRESHOLD,
+        }
+    }
+}
+
+// Petit à naître, avec le parent qui s'en occupera
+#[derive(Clone)]
+pub struct Embryo {
+    pub genome: Creature,
+    pub controller: Controller,
+    pub parents: (Entity, Entity),
+    pub guardian: Entity,
+}
+
+// 🥚 Embryon en incubation : se mange comme de la viande tant qu'il n'a pas éclos
+#[derive(Component)]
+#[require(Food)]
+pub struct Egg {
+    pub embryo: Embryo,
+    pub incubation: Timer,
+}
+
+impl Egg {
+    pub fn is_laid_by(&self, entity: Entity) -> bool {
+        self.embryo.parents.0 == entity || self.embryo.parents.1 == entity
+    }
+}
+
+fn switch_birth_mode(keyboard_input: Res<ButtonInput<KeyCode>>, mut reproduction: ResMut<Reproduction>) {
+    if keyboard_input.just_pressed(KeyCode::KeyB) {
+        reproduction.birth_mode = match reproduction.birth_mode {
+            BirthMode::Live => BirthMode::Egg,
+            BirthMode::Egg => BirthMode::Live,
+        };
+    }
+}
+
+fn switch_strategy(keyboard_input: Res<ButtonInput<KeyCode>>, mut reproduction: ResMut<Reproduction>) {
+    if keyboard_input.just_pressed(KeyCode::KeyN) {
+        reproduction.strategy = match reproduction.strategy {
+            ReproductionStrategy::Sexual => ReproductionStrategy::Asexual,
+            ReproductionStrategy::Asexual => ReproductionStrategy::Heritable,
+            ReproductionStrategy::Heritable => ReproductionStrategy::Sexual,
+        };
+    }
+}
+
+fn switch_two_sexes(keyboard_input: Res<ButtonInput<KeyCode>>, mut reproduction: ResMut<Reproduction>) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        reproduction.two_sexes = !reproduction.two_sexes;
+    }
+}
+
+pub fn lay_egg(commands: &mut Commands, position: Vec3, embryo: Embryo, image: Handle<Image>) {
+    commands.spawn((
+        Food { energy: embryo.genome.energy },
+        Egg {
+            incubation: Timer::from_seconds(embryo.genome.incubation_time, TimerMode::Once),
+            embryo,
+        },
+        Transform::from_translation(position),
+        Collider {
+            radius: EGG_RADIUS,
+            restitution: 0.,
+            layer: CollisionLayers::FOOD,
+            mask: CollisionLayers::CREATURE,
+            sensor: true,
+        },
+        Sprite {
+            image,
+            color: Color::srgb(1.0, 0.95, 0.8), // Blanc cassé pour les œufs
+            custom_size: Some(Vec2::new(EGG_RADIUS * 2.0, EGG_RADIUS * 2.0)),
+            ..default()
+        },
+    ));
+}
+
+fn hatch_eggs(
+    mut commands: Commands,
+    mut eggs: Query<(Entity, &mut Egg, &Transform)>,
+    mut allies: Query<&mut CreatureAllies>,
+    assets: Res<CreatureAssets>,
+    time: Res<Time>,
+) {
+    for (egg_entity, mut egg, transform) in eggs.iter_mut() {
+        egg.incubation.tick(time.delta());
+        if !egg.incubation.finished() {
+            continue;
+        }
+
+        commands.entity(egg_entity).despawn();
+        let baby_entity = spawn_newborn(egg.embryo.clone(), transform.translation.xy(), &mut commands, &assets);
+
+        // Les parents encore en vie reconnaissent le petit
+        for parent in [egg.embryo.parents.0, egg.embryo.parents.1] {
+            if let Ok(mut parent_allies) = allies.get_mut(parent) {
+                parent_allies.allies.insert(baby_entity);
+            }
+        }
+    }
+}
@@ -1,25 +1,116 @@
 use bevy::prelude::*;
 
+// Rayon pour lequel la masse vaut 1
+const REFERENCE_RADIUS: f32 = 30.;
+
 #[derive(Component, Default)] // Default permet d'ajouter la valeur apr défaut d'un type quand on initialise
-#[require(Accel, Transform)]
+#[require(Forces, PreviousAccel, Mass, LinearDamping, MaxSpeed, Transform)]
 pub struct Velocity(pub Vec2);
 
+// Somme des forces de la frame : le pilotage et les collisions y ajoutent, l'intégration la consomme puis la remet à zéro
+#[derive(Component, Default)]
+pub struct Forces(pub Vec2);
+
+// Accélération de la frame précédente, utilisée par l'intégration de Verlet
+#[derive(Component, Default)]
+pub struct PreviousAccel(pub Vec2);
+
+#[derive(Component)]
+pub struct Mass(pub f32);
+
+impl Default for Mass {
+    fn default() -> Self {
+        Mass(1.)
+    }
+}
+
+impl Mass {
+    // La masse suit la surface du disque
+    pub fn from_radius(radius: f32) -> Self {
+        Mass((radius / REFERENCE_RADIUS).powi(2))
+    }
+}
+
+// Fraction de la vitesse perdue par seconde
 #[derive(Component, Default)]
-pub struct Accel(pub Vec2);
+pub struct LinearDamping(pub f32);
 
+#[derive(Component)]
+pub struct MaxSpeed(pub f32);
+
+impl Default for MaxSpeed {
+    fn default() -> Self {
+        MaxSpeed(f32::INFINITY)
+    }
+}
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Integrator {
+    #[default]
+    SemiImplicitEuler,
+    Verlet,
+}
+
+
+// Ordre de la physique à chaque frame : pilotage, puis collisions, puis intégration
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PhysicsSet {
+    Steering,
+    Collision,
+    Integration,
+}
 
 pub struct RigidbodyPlugin;
 
 impl Plugin for RigidbodyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_rigidbody_position);
+        app.init_resource::<Integrator>()
+            .configure_sets(Update, (PhysicsSet::Steering, PhysicsSet::Collision, PhysicsSet::Integration).chain())
+            .add_systems(Update, switch_integrator)
+            .add_systems(Update, update_rigidbody_position.in_set(PhysicsSet::Integration));
     }
 }
 
-fn update_rigidbody_position(mut query: Query<(&mut Velocity, &Accel, &mut Transform)>, time: Res<Time>) {
-    for (mut velocity, accel, mut transform) in query.iter_mut() {
-        velocity.0 += accel.0 * time.delta_secs();
-        transform.translation += velocity.0.extend(0.) * time.delta_secs();
+fn switch_integrator(keyboard_input: Res<ButtonInput<KeyCode>>, mut integrator: ResMut<Integrator>) {
+    if keyboard_input.just_pressed(KeyCode::KeyV) {
+        *integrator = match *integrator {
+            Integrator::SemiImplicitEuler => Integrator::Verlet,
+            Integrator::Verlet => Integrator::SemiImplicitEuler,
+        };
     }
 }
 
+fn update_rigidbody_position(
+    mut query: Query<(&mut Velocity, &mut Forces, &mut PreviousAccel, &Mass, &LinearDamping, &MaxSpeed, &mut Transform)>,
+    integrator: Res<Integrator>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut velocity, mut forces, mut previous_accel, mass, damping, max_speed, mut transform) in query.iter_mut() {
+        let acceleration = forces.0 / mass.0;
+        forces.0 = Vec2::ZERO;
+
+        match *integrator {
+            Integrator::SemiImplicitEuler => {
+                // La vitesse est mise à jour avant la position
+                velocity.0 += acceleration * dt;
+                apply_damping_and_max_speed(&mut velocity, damping, max_speed, dt);
+                transform.translation += velocity.0.extend(0.) * dt;
+            }
+            Integrator::Verlet => {
+                // Verlet vitesse : position avec l'ancienne accélération, vitesse avec la moyenne des deux
+                transform.translation += (velocity.0 * dt + 0.5 * previous_accel.0 * dt * dt).extend(0.);
+                velocity.0 += 0.5 * (previous_accel.0 + acceleration) * dt;
+                apply_damping_and_max_speed(&mut velocity, damping, max_speed, dt);
+            }
+        }
+
+        previous_accel.0 = acceleration;
+    }
+}
+
+fn apply_damping_and_max_speed(velocity: &mut Velocity, damping: &LinearDamping, max_speed: &MaxSpeed, dt: f32) {
+    velocity.0 *= (-damping.0 * dt).exp();
+    velocity.0 = velocity.0.clamp_length_max(max_speed.0);
+}
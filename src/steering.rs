@@ -0,0 +1,131 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::creature::{order_float, Creature};
+use crate::rigidbody::Velocity;
+
+// Cercle d'errance de Reynolds : un point qui se déplace au hasard sur un cercle devant la créature
+#[derive(Component, Debug, Clone, Default)]
+pub struct Wander {
+    pub angle: f32,
+    pub direction: Vec2,
+}
+
+pub fn update_wander(
+    mut query: Query<(&mut Wander, &Creature, &Velocity)>,
+    time: Res<Time>,
+) {
+    let mut rng = rand::rng();
+
+    for (mut wander, creature, velocity) in query.iter_mut() {
+        // Marche aléatoire de l'angle, en racine du temps pour ne pas dépendre du nombre de frames
+        wander.angle += rng.random_range(-1.0..1.0) * creature.wander_turn_rate * time.delta_secs().sqrt();
+        wander.angle = (wander.angle + PI).rem_euclid(2. * PI) - PI;
+
+        // Plus la persistance est grande, plus le cercle est loin devant et plus la trajectoire est droite
+        let heading = velocity.0.try_normalize().unwrap_or(Vec2::X);
+        let circle_center = heading * creature.wander_persistence;
+        wander.direction = (circle_center + Vec2::from_angle(wander.angle).rotate(heading)).normalize_or_zero();
+    }
+}
+
+// ----- Comportements de pilotage de Reynolds -----
+// Chaque comportement renvoie une accélération d'au plus max_accel, à combiner avec combine
+
+const MAX_PREDICTION_TIME: f32 = 2.;
+
+// Une vitesse désirée devient une accélération qui corrige la vitesse actuelle
+fn steer_towards(desired_velocity: Vec2, velocity: Vec2, max_accel: f32) -> Vec2 {
+    (desired_velocity - velocity).clamp_length_max(max_accel)
+}
+
+pub fn seek(position: Vec2, velocity: Vec2, target: Vec2, max_speed: f32, max_accel: f32) -> Vec2 {
+    steer_towards((target - position).normalize_or_zero() * max_speed, velocity, max_accel)
+}
+
+// Suit la direction du cercle d'errance
+pub fn wander(position: Vec2, velocity: Vec2, wander_direction: Vec2, max_speed: f32, max_accel: f32) -> Vec2 {
+    seek(position, velocity, position + wander_direction, max_speed, max_accel)
+}
+
+pub fn flee(position: Vec2, velocity: Vec2, threat: Vec2, max_speed: f32, max_accel: f32) -> Vec2 {
+    steer_towards((position - threat).normalize_or_zero() * max_speed, velocity, max_accel)
+}
+
+// Comme seek, mais ralentit dans le rayon de freinage pour s'arrêter sur la cible
+pub fn arrive(position: Vec2, velocity: Vec2, target: Vec2, slowing_radius: f32, max_speed: f32, max_accel: f32) -> Vec2 {
+    let offset = target - position;
+    let speed = max_speed * (offset.length() / slowing_radius).min(1.);
+    steer_towards(offset.normalize_or_zero() * speed, velocity, max_accel)
+}
+
+// Vise la position future de la cible plutôt que sa position actuelle
+fn predict_position(position: Vec2, target: Vec2, target_velocity: Vec2, max_speed: f32) -> Vec2 {
+    let prediction_time = (position.distance(target) / max_speed.max(f32::EPSILON)).min(MAX_PREDICTION_TIME);
+    target + target_velocity * prediction_time
+}
+
+pub fn pursue(position: Vec2, velocity: Vec2, target: Vec2, target_velocity: Vec2, max_speed: f32, max_accel: f32) -> Vec2 {
+    seek(position, velocity, predict_position(position, target, target_velocity, max_speed), max_speed, max_accel)
+}
+
+pub fn evade(position: Vec2, velocity: Vec2, threat: Vec2, threat_velocity: Vec2, max_speed: f32, max_accel: f32) -> Vec2 {
+    flee(position, velocity, predict_position(position, threat, threat_velocity, max_speed), max_speed, max_accel)
+}
+
+// S'écarte des voisins trop proches, d'autant plus fort qu'ils sont près
+pub fn separation(position: Vec2, neighbours: &[(Vec2, f32)], own_radius: f32, max_accel: f32) -> Vec2 {
+    neighbours
+        .iter()
+        .filter_map(|(other_position, other_radius)| {
+            let offset = position - *other_position;
+            let comfort_distance = 2. * (own_radius + other_radius);
+            let distance = offset.length();
+            (distance > 0. && distance < comfort_distance)
+                .then(|| offset / distance * (1. - distance / comfort_distance))
+        })
+        .sum::<Vec2>()
+        .clamp_length_max(1.)
+        * max_accel
+}
+
+// Contourne l'obstacle le plus proche sur la trajectoire des prochaines secondes
+pub fn obstacle_avoidance(
+    position: Vec2,
+    velocity: Vec2,
+    obstacles: &[(Vec2, f32)],
+    own_radius: f32,
+    lookahead_time: f32,
+    max_accel: f32,
+) -> Vec2 {
+    let Some(heading) = velocity.try_normalize() else { return Vec2::ZERO; };
+    let lookahead = velocity.length() * lookahead_time;
+
+    obstacles
+        .iter()
+        .filter_map(|(obstacle_position, obstacle_radius)| {
+            let offset = *obstacle_position - position;
+            let ahead = offset.dot(heading);
+            let lateral = offset.perp_dot(heading);
+            let clearance = own_radius + obstacle_radius;
+            (ahead > 0. && ahead < lookahead && lateral.abs() < clearance).then_some((ahead, lateral))
+        })
+        .min_by(|(lhs, _), (rhs, _)| order_float(*lhs, *rhs))
+        .map(|(_, lateral)| {
+            // On tourne du côté opposé à l'obstacle
+            let side = if lateral >= 0. { heading.perp() } else { -heading.perp() };
+            side * max_accel
+        })
+        .unwrap_or(Vec2::ZERO)
+}
+
+// Somme pondérée de comportements, limitée à max_accel
+pub fn combine(behaviours: &[(Vec2, f32)], max_accel: f32) -> Vec2 {
+    behaviours
+        .iter()
+        .map(|(accel, weight)| *accel * *weight)
+        .sum::<Vec2>()
+        .clamp_length_max(max_accel)
+}